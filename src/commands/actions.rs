@@ -1,11 +1,11 @@
+use crate::config::Config;
 use crate::error::Result;
 use crate::output::Format;
 use crate::parser::{env, github_actions};
 use colored::*;
-use std::collections::HashSet;
 use std::path::Path;
 
-pub fn run(dir: &Path, env_path: &Path, _format: Format) -> Result<()> {
+pub fn run(dir: &Path, env_path: &Path, _format: Format, config: &Config) -> Result<()> {
     // 1. Parse Directory (recursively scanning for .yml/.yaml)
     // Note: The parser currently scans the whole dir.
     // If the user passes ".", we might want to default to ".github/workflows" if it exists,
@@ -19,10 +19,9 @@ pub fn run(dir: &Path, env_path: &Path, _format: Format) -> Result<()> {
     let refs = github_actions::parse_directory(dir)?;
 
     // 2. Parse .env file
-    let env_file = env::EnvFile::parse(env_path)?;
-    let env_entries = env_file.vars;
-
-    let env_keys: HashSet<String> = env_entries.into_iter().map(|e| e.key).collect();
+    let env_file = env::EnvFile::parse_with_dialect(env_path, config.dialect_for(env_path))?;
+    // Keys marked optional may be missing
+    let optional = env_file.optional_keys();
 
     // 3. Compare
     let mut missing_in_env = Vec::new();
@@ -34,7 +33,9 @@ pub fn run(dir: &Path, env_path: &Path, _format: Format) -> Result<()> {
         // Actually, often in local dev we want `MY_KEY=...` in .env.
         // The parser extracts keys from `env:`.
 
-        if !env_keys.contains(&reference.env_var) {
+        if env_file.resolved_value(&reference.env_var).is_none()
+            && !config.is_optional(&reference.env_var, &optional)
+        {
             missing_in_env.push(reference);
         }
     }
@@ -1,26 +1,27 @@
+use crate::config::Config;
 use crate::error::Result;
 use crate::output::Format;
 use crate::parser::argocd::EnvSource;
 use crate::parser::{argocd, env};
 use colored::*;
-use std::collections::HashSet;
 use std::path::Path;
 
-pub fn run(dir: &Path, env_path: &Path, _format: Format) -> Result<()> {
+pub fn run(dir: &Path, env_path: &Path, _format: Format, config: &Config) -> Result<()> {
     // 1. Parse Argo Application manifests
     let refs = argocd::parse_directory(dir)?;
 
     // 2. Parse .env file
-    let env_file = env::EnvFile::parse(env_path)?;
-    let env_entries = env_file.vars;
-
-    let env_keys: HashSet<String> = env_entries.into_iter().map(|e| e.key).collect();
+    let env_file = env::EnvFile::parse_with_dialect(env_path, config.dialect_for(env_path))?;
+    // Keys marked optional may be missing
+    let optional = env_file.optional_keys();
 
     // 3. Compare
     let mut missing_in_env = Vec::new();
 
     for reference in &refs {
-        if !env_keys.contains(&reference.env_var) {
+        if env_file.resolved_value(&reference.env_var).is_none()
+            && !config.is_optional(&reference.env_var, &optional)
+        {
             missing_in_env.push(reference);
         }
     }
@@ -1,83 +1,177 @@
 use std::collections::HashSet;
-use std::io;
 use std::path::PathBuf;
 
+use clap::ValueEnum;
+
+use crate::config::Config;
+use crate::diff::KeyChanges;
 use crate::error::{EnvCheckError, Result};
-use crate::output::{write_diagnostics, Format};
+use crate::git::Git;
+use crate::output::{write_changes, Format, KeyMatrix, Sources};
 use crate::parser::EnvFile;
-use crate::rules::{Diagnostic, RuleId, Severity};
+use crate::rules::schema::SchemaRule;
+use crate::rules::values::{Redaction, ValueComparison};
+use crate::rules::{Diagnostic, Rule, RuleId, Severity, Span};
+
+/// How `envcheck compare` relates the files to each other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CompareMode {
+    /// Report keys of the first file missing from the others
+    #[default]
+    Reference,
+    /// Also report keys missing from the first file
+    Symmetric,
+    /// Print a key × file presence table
+    Matrix,
+}
 
-pub fn run(files: &[PathBuf], format: Format) -> Result<()> {
+/// Options for comparing values, on top of the configured `compare` key lists.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValueOptions {
+    /// Report every value that differs between files (`--values`).
+    pub report: bool,
+    pub redact: Redaction,
+}
+
+pub fn run(
+    files: &[PathBuf],
+    mode: CompareMode,
+    values: ValueOptions,
+    git_ref: Option<&str>,
+    format: Format,
+    config: &Config,
+) -> Result<()> {
+    if let Some(rev) = git_ref {
+        return compare_with_revision(files, rev, format, config);
+    }
     if files.len() < 2 {
         return Err(EnvCheckError::InsufficientFiles { count: files.len() });
     }
 
+    if mode == CompareMode::Matrix {
+        format.require_document("the key matrix")?;
+    }
+
     // Parse all files
     let mut env_files = Vec::new();
     for path in files {
-        let env_file = EnvFile::parse(path)?;
+        let env_file = EnvFile::parse_with_dialect(path, config.dialect_for(path))?;
         env_files.push(env_file);
     }
 
-    // The first file is the "reference" (e.g., .env.example)
-    // Actually, usually we want to cross-compare everything, or use the first as base.
-    // The requirement says: "compare .env.example .env.local .env.prod"
-    // "Key missing in comparison file" (W004)
-    // Implicitly, usually .env.example is the source of truth.
-    // Let's implement: unique union of all keys, or just use File[0] as Set A?
-    // "Compare keys across files" often means checks consistency.
-    // Let's take the UNION of all keys, OR just rely on File[0] as the strict definition?
-    //envdoc-go documentation says it finds "Missing keys across files".
-    // Let's check: For every file A, and every other file B, are there keys in A missing in B?
-    // Or simpler: Use File[0] as the "reference". Any key in File[0] MUST exist in File[1..N].
-    // Also, keys in File[1..N] that are NOT in File[0] might be "Extra keys" (Info/Warning?).
-    // Let's explicitly check: Reference vs Others.
+    if mode == CompareMode::Matrix {
+        let mut stdout = std::io::stdout().lock();
+        return KeyMatrix::new(&env_files)
+            .write(format, &mut stdout)
+            .map_err(|e| EnvCheckError::write_error("stdout", e));
+    }
 
+    // The first file is the reference (e.g., .env.example)
     let reference = &env_files[0];
-    let others = &env_files[1..];
-
-    let ref_keys: HashSet<&String> = reference.vars.iter().map(|v| &v.key).collect();
+    let ref_keys: HashSet<&str> = reference.vars.iter().map(|v| v.key.as_str()).collect();
+    let optional = reference.optional_keys();
 
     let mut diagnostics = Vec::new();
 
-    for other in others {
-        let other_keys: HashSet<&String> = other.vars.iter().map(|v| &v.key).collect();
+    for other in &env_files[1..] {
+        let other_keys: HashSet<&str> = other.vars.iter().map(|v| v.key.as_str()).collect();
 
-        // Check for keys in Reference missing in Other (W004)
-        for &key in &ref_keys {
-            if !other_keys.contains(key) {
+        // Keys in the reference missing from the other file (W004)
+        let mut reported = HashSet::new();
+        for var in &reference.vars {
+            if !other_keys.contains(var.key.as_str()) && reported.insert(var.key.as_str()) {
+                // Optional keys are reported as info, so they don't drown real drift
+                let (severity, kind) = if config.is_optional(&var.key, &optional) {
+                    (Severity::Info, "optional key")
+                } else {
+                    (Severity::Warning, "key")
+                };
                 diagnostics.push(Diagnostic {
                     id: RuleId::W004,
-                    severity: Severity::Warning,
+                    severity,
                     message: format!(
-                        "Missing key '{}' (present in {})",
-                        key,
+                        "Missing {kind} '{}' (present in {})",
+                        var.key,
                         reference.path.display()
                     ),
                     path: other.path.clone(),
                     line: None, // We don't have a line number for a missing key
+                    span: None,
+                    labels: Vec::new(),
+                    constraint: None,
+                    fix: None,
                 });
             }
         }
 
-        // Optional: Check for keys in Other missing in Reference (Reverse check)
-        // Usually local envs might have extra secrets not in example? Or example should list everything?
-        // Let's add it as Info or specialized Warning?
-        // Requirement table says: `W004 | Key missing in comparison file | Warning`
-        // Let's stick to Reference -> Other direction as primary warning.
+        // Keys in the other file the reference doesn't list, even commented out (W017)
+        if mode == CompareMode::Symmetric {
+            let mut reported = HashSet::new();
+            for var in &other.vars {
+                if !ref_keys.contains(var.key.as_str())
+                    && !config.is_optional(&var.key, &optional)
+                    && reported.insert(var.key.as_str())
+                {
+                    diagnostics.push(Diagnostic {
+                        id: RuleId::W017,
+                        severity: Severity::Warning,
+                        message: format!(
+                            "Extra key '{}' (not in {})",
+                            var.key,
+                            reference.path.display()
+                        ),
+                        path: other.path.clone(),
+                        line: Some(var.line),
+                        span: Some(Span::new(other, var.key_span.clone())),
+                        labels: Vec::new(),
+                        constraint: None,
+                        fix: None,
+                    });
+                }
+            }
+        }
+    }
+
+    diagnostics.extend(
+        ValueComparison::new(&config.compare, values.redact, values.report).check(&env_files),
+    );
+
+    // With a schema, every file's values must also satisfy their contracts
+    if let Some(schema) = &config.env_schema {
+        let rule = SchemaRule::new(schema);
+        for env_file in &env_files {
+            diagnostics.extend(rule.check(env_file));
+        }
     }
 
-    let stdout = io::stdout();
-    let mut handle = stdout.lock();
-    write_diagnostics(format, &diagnostics, &mut handle)
-        .map_err(|e| EnvCheckError::read_error("stdout", e))?;
+    // Presence warnings are only failures when the config promotes them to errors.
+    let mut sources = Sources::new();
+    for env_file in &env_files {
+        sources.insert(env_file);
+    }
+    super::report(&config.apply(diagnostics), format, config, &sources)
+}
+
+/// Reports the keys added, removed or renamed in each file since `rev`.
+fn compare_with_revision(
+    files: &[PathBuf],
+    rev: &str,
+    format: Format,
+    config: &Config,
+) -> Result<()> {
+    format.require_document("key changes")?;
 
-    // If warnings found, maybe exit non-zero?
-    // "Lint failed" error usually just checks error_count > 0.
-    // But compare is utility. Let's return Ok, unless we strictly want to fail CI.
-    // Our Error types handle failure.
-    // If diagnostics exist, we might want to return `Ok` but the user sees output.
-    // Only return Error if execution failed.
+    let mut changes = Vec::new();
+    for path in files {
+        let dialect = config.dialect_for(path);
+        let new = EnvFile::parse_with_dialect(path, dialect)?;
+        // A file that did not exist at `rev` has only added keys
+        let old_content = Git::show_file(rev, path)?.unwrap_or_default();
+        let old = EnvFile::parse_content_with_dialect(path.clone(), &old_content, dialect)?;
+        changes.push(KeyChanges::between(&old, &new).labelled(rev, "working copy"));
+    }
 
-    Ok(())
+    let mut stdout = std::io::stdout().lock();
+    write_changes(format, &changes, &mut stdout)
+        .map_err(|e| EnvCheckError::write_error("stdout", e))
 }
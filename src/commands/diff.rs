@@ -0,0 +1,100 @@
+//! `envcheck diff`: key-level differences between two .env files.
+//!
+//! Besides `envcheck diff old.env new.env`, it accepts the arguments git passes
+//! to an external diff driver (`GIT_EXTERNAL_DIFF` or `diff.<name>.command`):
+//! seven for a changed file, nine for a rename or copy, one for an unmerged file.
+//! `--textconv FILE` prints a normalized key listing for git's own diff.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::diff::EnvDiff;
+use crate::error::{EnvCheckError, Result};
+use crate::output::{write_diff, Format};
+use crate::parser::EnvFile;
+use crate::rules::values::Redaction;
+
+pub fn run(
+    files: &[PathBuf],
+    breaking: bool,
+    textconv: bool,
+    redaction: Redaction,
+    format: Format,
+    config: &Config,
+) -> Result<()> {
+    let count = files.len();
+    if textconv {
+        let [path] = files else {
+            return Err(EnvCheckError::DiffArguments { count });
+        };
+        return write_textconv(path, redaction, config);
+    }
+
+    // `(path, contents)` of each side; git passes temporary copies as contents
+    let (old, new) = match files {
+        [old, new] => ((old, old), (new, new)),
+        // path old-file old-hex old-mode new-file new-hex new-mode
+        [path, old, _, _, new, _, _] => ((path, old), (path, new)),
+        // The same, plus the new path and rename metadata for renames and copies
+        [path, old, _, _, new, _, _, new_path, _] => ((path, old), (new_path, new)),
+        // Git passes only the path of an unmerged file; it sets `GIT_DIFF_PATH_TOTAL`
+        // whenever it runs an external diff
+        [path] if std::env::var_os("GIT_DIFF_PATH_TOTAL").is_some() => {
+            println!("* Unmerged path {}", path.display());
+            return Ok(());
+        },
+        _ => return Err(EnvCheckError::DiffArguments { count }),
+    };
+    let (from, to) = if files.len() == 2 {
+        (old.0.display().to_string(), new.0.display().to_string())
+    } else {
+        (
+            format!("a/{}", old.0.display()),
+            format!("b/{}", new.0.display()),
+        )
+    };
+
+    format.require_document("a diff")?;
+    let old = parse(old.1, old.0, config)?;
+    let new = parse(new.1, new.0, config)?;
+    let diff = EnvDiff::between(&old, &new, redaction, config).labelled(from, to);
+
+    let mut stdout = std::io::stdout().lock();
+    write_diff(format, &diff, breaking, &mut stdout)
+        .map_err(|e| EnvCheckError::write_error("stdout", e))?;
+
+    if breaking && !diff.breaking.is_empty() {
+        return Err(EnvCheckError::BreakingChanges {
+            keys: diff.breaking,
+        });
+    }
+    Ok(())
+}
+
+/// Parses `file` as the content of `path`, which may be a temporary copy git
+/// made (or `/dev/null` for a file that did not exist).
+fn parse(file: &Path, path: &Path, config: &Config) -> Result<EnvFile> {
+    let content = std::fs::read_to_string(file).map_err(|e| EnvCheckError::read_error(file, e))?;
+    EnvFile::parse_content_with_dialect(path.to_path_buf(), &content, config.dialect_for(path))
+}
+
+/// Prints one `KEY=<redacted value>` line per definition, so formatting and
+/// comment edits disappear from `git diff` and values never reach the pager.
+/// Empty values stay empty.
+fn write_textconv(path: &Path, redaction: Redaction, config: &Config) -> Result<()> {
+    let env_file = parse(path, path, config)?;
+    let mut stdout = std::io::stdout().lock();
+    env_file
+        .vars
+        .iter()
+        .try_for_each(|var| {
+            let value = if var.value.is_empty() {
+                String::new()
+            } else {
+                redaction.apply(&var.value)
+            };
+            writeln!(stdout, "{}={value}", var.key)
+        })
+        .map_err(|e| EnvCheckError::write_error("stdout", e))
+}
@@ -1,8 +1,9 @@
+use crate::config::Config;
 use crate::error::Result;
 use crate::output::Format;
 
 /// Runs `lint` on all .env files in the current directory.
-pub fn run(format: Format) -> Result<()> {
+pub fn run(format: Format, config: &Config) -> Result<()> {
     // Glob for .env*
     let patterns = [".env", ".env.*"];
     let mut files = Vec::new();
@@ -15,7 +16,7 @@ pub fn run(format: Format) -> Result<()> {
             })?
             .flatten()
         {
-            if path.is_file() {
+            if path.is_file() && !config.is_ignored(&path) {
                 files.push(path);
             }
         }
@@ -27,5 +28,5 @@ pub fn run(format: Format) -> Result<()> {
     }
 
     println!("Running doctor on {} files...", files.len());
-    crate::commands::lint::run(&files, format)
+    crate::commands::lint::run(&files, None, format, config)
 }
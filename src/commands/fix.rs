@@ -1,178 +1,227 @@
+use crate::config::{Config, FixConfig};
 use crate::error::{EnvCheckError, Result};
+use crate::git::{self, Git};
+use crate::parser::env::SyntaxTree;
+use crate::parser::section::{sections, SectionEntry};
+use crate::parser::EnvFile;
+use crate::rules::sort::KeyOrder;
+use crate::rules::{check_file_with_config, fix, Fix};
+use similar::TextDiff;
+use std::ffi::OsStr;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-
-#[derive(Debug, Clone)]
-struct EnvEntry {
-    /// Comments preceding the key (or file header comments)
-    comments: Vec<String>,
-    /// The actual raw line containing the key (trimmed of whitespace, but keeping content)
-    /// If None, this is just a standalone comment block (like a header)
-    key_line: Option<String>,
-    /// The extracted key for sorting
-    key: Option<String>,
-    #[allow(dead_code)]
-    /// Original line (allows detecting blank lines to preserve spacing if needed)
-    original_lines: Vec<String>,
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Rule fixes are re-run this many times at most, so fixes skipped for
+/// conflicting with others get another chance.
+const MAX_FIX_PASSES: usize = 8;
+
+/// Where `envcheck fix` sends its result. Files are only written when no flag is set.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixMode {
+    /// Fail if any file would change.
+    pub check: bool,
+    /// Print a unified diff of the changes.
+    pub diff: bool,
+    /// Print the fixed content.
+    pub stdout: bool,
 }
 
-pub fn run(files: &[PathBuf], commit: bool, pr: bool) -> Result<()> {
-    for path in files {
-        fix_file(path)?;
+impl FixMode {
+    const fn writes(self) -> bool {
+        !(self.check || self.diff || self.stdout)
     }
+}
 
-    if commit || pr {
-        // Stage the fixed files
-        let file_args: Vec<&str> = files.iter().filter_map(|p| p.to_str()).collect();
-        let _ = Command::new("git").arg("add").args(&file_args).status();
-
-        // Commit
-        let _ = Command::new("git")
-            .args(["commit", "-m", "fix: auto-fix .env files via envcheck"])
-            .status();
-
-        if pr {
-            // Create PR using gh CLI
-            let _ = Command::new("gh")
-                .args([
-                    "pr",
-                    "create",
-                    "--fill",
-                    "--title",
-                    "fix: auto-fix .env files",
-                ])
-                .status();
+pub fn run(
+    files: &[PathBuf],
+    commit: bool,
+    pr: bool,
+    mode: FixMode,
+    config: &Config,
+) -> Result<()> {
+    let publish = mode.writes() && (commit || pr);
+    let git = Git::new(".");
+    if publish {
+        // Anything already staged would end up in the fix commit
+        let staged = git.staged_files()?;
+        if !staged.is_empty() {
+            return Err(EnvCheckError::DirtyIndex { files: staged });
         }
     }
 
-    Ok(())
-}
+    let mut out = std::io::stdout().lock();
+    let mut changed = Vec::new();
 
-fn fix_file(path: &Path) -> Result<()> {
-    let content = fs::read_to_string(path).map_err(|e| EnvCheckError::read_error(path, e))?;
+    for path in files {
+        let original = fs::read_to_string(path).map_err(|e| EnvCheckError::read_error(path, e))?;
+        let fixed = fix_content(path, &original, config)?;
 
-    if content.trim().is_empty() {
-        return Ok(());
-    }
+        if mode.stdout {
+            let _ = out.write_all(fixed.as_bytes());
+        }
+        if fixed == original {
+            continue;
+        }
 
-    let entries = parse_preserving(path, &content);
+        if mode.diff {
+            // Relative paths get git-style prefixes so the diff applies with `git apply`
+            let name = path.display().to_string();
+            let (old, new) = if path.is_absolute() {
+                (name.clone(), name)
+            } else {
+                (format!("a/{name}"), format!("b/{name}"))
+            };
+            let diff = TextDiff::from_lines(&original, &fixed);
+            let _ = write!(out, "{}", diff.unified_diff().header(&old, &new));
+        }
+        changed.push((path.as_path(), fixed));
+    }
 
-    let mut header = Vec::new();
-    let mut footer = Vec::new();
-    let mut body = Vec::new();
+    if mode.check && !changed.is_empty() {
+        return Err(EnvCheckError::FixNeeded {
+            count: changed.len(),
+        });
+    }
+    if !mode.writes() || changed.is_empty() {
+        return Ok(());
+    }
 
-    let mut in_header = true;
+    // Branch off before writing, so a git failure leaves the current branch untouched
+    let branch = if publish {
+        Some(create_branch(&git, &config.fix)?)
+    } else {
+        None
+    };
+    for (path, fixed) in &changed {
+        fs::write(path, fixed).map_err(|e| EnvCheckError::write_error(path, e))?;
+    }
 
-    for entry in entries {
-        if entry.key.is_some() {
-            in_header = false;
-            body.push(entry);
-        } else if in_header {
-            header.push(entry);
-        } else {
-            // If we are not in header, and hit a no-key, it's either footer or intermediate.
-            // Treat as footer for now.
-            footer.push(entry);
-        }
+    if let Some(branch) = branch {
+        let paths: Vec<&Path> = changed.iter().map(|(path, _)| *path).collect();
+        let output = publish_fixes(&git, &branch, &paths, pr, &config.fix)?;
+        let _ = out.write_all(output.as_bytes());
     }
 
-    // Sort body
-    body.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(())
+}
 
-    let mut final_output = String::new();
+/// Creates and switches to the branch for the fix commit, returning its name.
+fn create_branch(git: &Git, config: &FixConfig) -> Result<String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let branch = config.branch.replace("{timestamp}", &timestamp.to_string());
+    git.run(["checkout", "-b", &branch])?;
+    Ok(branch)
+}
 
-    for e in header {
-        final_output.push_str(&render_entry(&e));
+/// Commits the changed files on `branch`. With `pr`, also pushes the branch
+/// and runs the PR command, returning its output.
+fn publish_fixes(
+    git: &Git,
+    branch: &str,
+    files: &[&Path],
+    pr: bool,
+    config: &FixConfig,
+) -> Result<String> {
+    git.run(
+        [OsStr::new("add"), OsStr::new("--")]
+            .into_iter()
+            .chain(files.iter().map(|path| path.as_os_str())),
+    )?;
+    git.run(["commit", "-m", &config.commit_message])?;
+
+    if !pr {
+        return Ok(String::new());
     }
+    git.run(["push", "--set-upstream", &config.remote, branch])?;
+
+    let Some((program, args)) = config.pr_command.split_first() else {
+        return Ok(String::new());
+    };
+    let substitute = |arg: &String| {
+        arg.replace("{branch}", branch)
+            .replace("{message}", &config.commit_message)
+    };
+    let mut command = Command::new(substitute(program));
+    command.args(args.iter().map(substitute));
+    git::run(&mut command).map_err(|message| EnvCheckError::PrFailed {
+        command: git::describe(&command),
+        message,
+    })
+}
 
-    for e in body {
-        final_output.push_str(&render_entry(&e));
+/// Returns `content` with rule fixes applied and keys sorted.
+fn fix_content(path: &Path, content: &str, config: &Config) -> Result<String> {
+    if content.trim().is_empty() {
+        return Ok(content.to_string());
     }
 
-    for e in footer {
-        final_output.push_str(&render_entry(&e));
+    let content = apply_fixes(path, content.to_string(), config)?;
+    let tree = SyntaxTree::parse_with_dialect(&content, config.dialect_for(path));
+    let order = KeyOrder::new(&config.sort);
+    let key = |entry: &SectionEntry| tree.key(&tree.nodes()[entry.node]).unwrap_or_default();
+
+    let mut output = String::new();
+    for mut section in sections(&tree) {
+        section
+            .entries
+            .sort_by(|a, b| order.compare(key(a), key(b)));
+
+        render(&tree, &section.header, &mut output);
+        for entry in &section.entries {
+            render(&tree, &entry.comments, &mut output);
+            render(&tree, &[entry.node], &mut output);
+        }
+        render(&tree, &section.trailer, &mut output);
     }
 
-    // Usually we want one final newline.
-    if !final_output.ends_with('\n') {
-        final_output.push('\n');
+    if !output.ends_with('\n') {
+        output.push('\n');
     }
 
-    fs::write(path, final_output).map_err(|e| EnvCheckError::write_error(path, e))?;
-
-    Ok(())
+    Ok(output)
 }
 
-fn parse_preserving(_path: &Path, content: &str) -> Vec<EnvEntry> {
-    let mut entries = Vec::new();
-    let mut current_comments = Vec::new();
-
-    for line in content.lines() {
-        let trimmed = line.trim();
-
-        if trimmed.is_empty() {
-            // Blank line.
-            // If we have accumulated comments, flush them as a distinct block (e.g. Header).
-            if !current_comments.is_empty() {
-                entries.push(EnvEntry {
-                    comments: current_comments.clone(),
-                    key_line: None,
-                    key: None,
-                    original_lines: vec![],
-                });
-                current_comments.clear();
-            }
-            continue;
-        }
-
-        if trimmed.starts_with('#') {
-            current_comments.push(String::from(trimmed));
-            continue;
+/// Applies the fixes attached to diagnostics, plus `export` removal if configured.
+fn apply_fixes(path: &Path, mut content: String, config: &Config) -> Result<String> {
+    let dialect = config.dialect_for(path);
+
+    for _ in 0..MAX_FIX_PASSES {
+        let env_file = EnvFile::parse_content_with_dialect(path.to_path_buf(), &content, dialect)?;
+        let mut fixes: Vec<Fix> = config
+            .apply(check_file_with_config(&env_file, config))
+            .into_iter()
+            .filter_map(|d| d.fix)
+            .collect();
+        if config.fix.remove_export {
+            fixes.extend(
+                env_file
+                    .vars
+                    .iter()
+                    .filter(|var| var.exported)
+                    .filter_map(|var| Fix::remove_export(&env_file, var.line)),
+            );
         }
 
-        // It's a key line (or invalid garbage)
-        // Check if key
-        if let Some((k, _)) = trimmed.split_once('=') {
-            let key_str = k.trim().trim_start_matches("export ").trim().to_string();
-            // Valid-ish key.
-            entries.push(EnvEntry {
-                comments: current_comments.clone(),
-                key_line: Some(trimmed.to_string()),
-                key: Some(key_str),
-                original_lines: vec![],
-            });
-            current_comments.clear();
-        } else {
-            // Garbage or complex line?
-            // Treat as comment/blob to be safe?
-            // If we treat as comment, it gets attached to next key.
-            current_comments.push(String::from(trimmed));
+        let (fixed, applied) = fix::apply(&content, &fixes);
+        if applied == 0 {
+            break;
         }
+        content = fixed;
     }
 
-    // EOF. If comments remain, add as Footer entry
-    if !current_comments.is_empty() {
-        entries.push(EnvEntry {
-            comments: current_comments,
-            key_line: None,
-            key: None,
-            original_lines: vec![],
-        });
-    }
-
-    entries
+    Ok(content)
 }
 
-fn render_entry(entry: &EnvEntry) -> String {
-    let mut s = String::new();
-    for comment in &entry.comments {
-        s.push_str(comment);
-        s.push('\n');
-    }
-    if let Some(ref line) = entry.key_line {
-        s.push_str(line);
-        s.push('\n');
+/// Appends nodes without trailing whitespace, one line ending each.
+fn render(tree: &SyntaxTree, nodes: &[usize], output: &mut String) {
+    for &i in nodes {
+        // Multi-line values are a single node, so they move as one unit
+        output.push_str(tree.node_text(&tree.nodes()[i]).trim());
+        output.push('\n');
     }
-    s
 }
@@ -2,15 +2,22 @@ use std::collections::HashSet;
 use std::io;
 use std::path::Path;
 
+use crate::config::Config;
 use crate::error::{EnvCheckError, Result};
-use crate::output::{write_diagnostics, Format};
+use crate::output::{Format, Sources};
 use crate::parser::{EnvFile, K8sManifest, K8sRefSource};
-use crate::rules::{Diagnostic, RuleId, Severity};
-
-pub fn run(manifest_patterns: &[String], env_path: &Path, format: Format) -> Result<()> {
+use crate::rules::{Diagnostic, RuleId, Severity, Span};
+
+pub fn run(
+    manifest_patterns: &[String],
+    env_path: &Path,
+    format: Format,
+    config: &Config,
+) -> Result<()> {
     // 1. Parse .env file
-    let env_file = EnvFile::parse(env_path)?;
+    let env_file = EnvFile::parse_with_dialect(env_path, config.dialect_for(env_path))?;
     let env_keys: HashSet<String> = env_file.vars.iter().map(|v| v.key.clone()).collect();
+    let optional = env_file.optional_keys();
 
     // 2. Parse all K8s manifests
     let mut manifests = Vec::new();
@@ -24,7 +31,7 @@ pub fn run(manifest_patterns: &[String], env_path: &Path, format: Format) -> Res
                 Ok(path) => {
                     // Try parsing as K8s manifest
                     // If it's a directory, skip? Glob usually returns files.
-                    if path.is_file() {
+                    if path.is_file() && !config.is_ignored(&path) {
                         let parsed = K8sManifest::parse(path)?;
                         manifests.extend(parsed);
                     }
@@ -78,6 +85,15 @@ pub fn run(manifest_patterns: &[String], env_path: &Path, format: Format) -> Res
         }
     }
 
+    // Keys the .env file marks optional are reported as info
+    let missing_severity = |key: &str| {
+        if config.is_optional(key, &optional) {
+            (Severity::Info, "Optional key")
+        } else {
+            (Severity::Warning, "Key")
+        }
+    };
+
     // Check W005: Key defined in K8s (Secret/CM) but missing in .env
     // We scan k8s_defined_keys.
     for k8s_key in &k8s_defined_keys {
@@ -94,15 +110,20 @@ pub fn run(manifest_patterns: &[String], env_path: &Path, format: Format) -> Res
                             K8sRefSource::SecretData | K8sRefSource::ConfigMapData
                         )
                     {
+                        let (severity, kind) = missing_severity(k8s_key);
                         diagnostics.push(Diagnostic {
                             id: RuleId::W005,
-                            severity: Severity::Warning,
+                            severity,
                             message: format!(
-                                "Key '{}' found in K8s {}/{} but missing in .env",
+                                "{kind} '{}' found in K8s {}/{} but missing in .env",
                                 k8s_key, m.kind, m.name
                             ),
                             path: m.path.clone(),
                             line: None, // YAML parser didn't give lines, could add later
+                            span: None,
+                            labels: Vec::new(),
+                            constraint: None,
+                            fix: None,
                         });
                     }
                 }
@@ -124,15 +145,20 @@ pub fn run(manifest_patterns: &[String], env_path: &Path, format: Format) -> Res
                             K8sRefSource::SecretData | K8sRefSource::ConfigMapData
                         )
                     {
+                        let (severity, kind) = missing_severity(k8s_key);
                         diagnostics.push(Diagnostic {
                             id: RuleId::W005,
-                            severity: Severity::Warning,
+                            severity,
                             message: format!(
-                                "Key '{}' referenced in K8s {}/{} but missing in .env",
+                                "{kind} '{}' referenced in K8s {}/{} but missing in .env",
                                 k8s_key, m.kind, m.name
                             ),
                             path: m.path.clone(),
                             line: None,
+                            span: None,
+                            labels: Vec::new(),
+                            constraint: None,
+                            fix: None,
                         });
                     }
                 }
@@ -147,18 +173,18 @@ pub fn run(manifest_patterns: &[String], env_path: &Path, format: Format) -> Res
     for env_key in &env_keys {
         if !all_k8s_keys.contains(env_key) {
             // Find line in .env
-            let line = env_file
-                .vars
-                .iter()
-                .find(|v| &v.key == env_key)
-                .map(|v| v.line);
+            let var = env_file.vars.iter().find(|v| &v.key == env_key);
 
             diagnostics.push(Diagnostic {
                 id: RuleId::W006,
                 severity: Severity::Info,
                 message: format!("Key '{env_key}' in .env but not found in any K8s manifest"),
                 path: env_file.path.clone(),
-                line,
+                line: var.map(|v| v.line),
+                span: var.map(|v| Span::new(&env_file, v.key_span.clone())),
+                labels: Vec::new(),
+                constraint: None,
+                fix: None,
             });
         }
     }
@@ -166,10 +192,7 @@ pub fn run(manifest_patterns: &[String], env_path: &Path, format: Format) -> Res
     // Deduplicate diagnostics? (Same key in multiple manifests might spam)
     // For now keep all.
 
-    let stdout = io::stdout();
-    let mut handle = stdout.lock();
-    write_diagnostics(format, &diagnostics, &mut handle)
-        .map_err(|e| EnvCheckError::read_error("stdout", e))?;
-
-    Ok(())
+    let mut sources = Sources::new();
+    sources.insert(&env_file);
+    super::report(&config.apply(diagnostics), format, config, &sources)
 }
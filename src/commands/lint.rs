@@ -1,64 +1,81 @@
-use std::io;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use rayon::prelude::*;
 
+use crate::config::Config;
 use crate::error::Result;
-use crate::output::{write_diagnostics, Format};
+use crate::git::Git;
+use crate::output::{Format, Sources};
 use crate::parser::EnvFile;
-use crate::rules::{check_file, Diagnostic, Severity};
+use crate::rules::{check_file_with_config, Diagnostic, RuleId};
 
-pub fn run(files: &[PathBuf], format: Format) -> Result<()> {
-    let error_count = AtomicUsize::new(0);
-    let warning_count = AtomicUsize::new(0);
-
-    // Process files in parallel
+pub fn run(
+    files: &[PathBuf],
+    git_ref: Option<&str>,
+    format: Format,
+    config: &Config,
+) -> Result<()> {
+    // Process files in parallel, skipping those matched by ignore patterns
     let results: Vec<_> = files
         .par_iter()
-        .map(|path| match EnvFile::parse(path) {
-            Ok(env_file) => {
-                let diagnostics = check_file(&env_file);
-                for d in &diagnostics {
-                    match d.severity {
-                        Severity::Error => {
-                            error_count.fetch_add(1, Ordering::Relaxed);
-                        },
-                        Severity::Warning => {
-                            warning_count.fetch_add(1, Ordering::Relaxed);
-                        },
-                        _ => {},
-                    }
-                }
-                Ok(diagnostics)
-            },
-            Err(e) => Err(e),
+        .filter(|path| !config.is_ignored(path))
+        .map(|path| {
+            let env_file = EnvFile::parse_with_dialect(path, config.dialect_for(path))?;
+            let mut diagnostics = config.apply(check_file_with_config(&env_file, config));
+            if let Some(rev) = git_ref {
+                diagnostics = new_since(rev, path, diagnostics, config)?;
+            }
+            Ok((diagnostics, env_file))
         })
         .collect();
 
     // Flatten results, propagating first error if any
     let mut all_diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut sources = Sources::new();
     for result in results {
-        match result {
-            Ok(diagnostics) => all_diagnostics.extend(diagnostics),
-            Err(e) => return Err(e),
-        }
+        let (diagnostics, env_file) = result?;
+        all_diagnostics.extend(diagnostics);
+        sources.insert(&env_file);
     }
 
     // Sort all diagnostics by File path then Line
     all_diagnostics.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
 
-    let stdout = io::stdout();
-    let mut handle = stdout.lock();
-    write_diagnostics(format, &all_diagnostics, &mut handle)
-        .map_err(|e| crate::error::EnvCheckError::read_error("stdout", e))?;
+    super::report(&all_diagnostics, format, config, &sources)
+}
 
-    if error_count.load(Ordering::Relaxed) > 0 {
-        Err(crate::error::EnvCheckError::LintFailed {
-            error_count: error_count.load(Ordering::Relaxed),
-            warning_count: warning_count.load(Ordering::Relaxed),
-        })
-    } else {
-        Ok(())
+/// Drops the diagnostics `path` already had at `rev`, matched by rule and message.
+fn new_since(
+    rev: &str,
+    path: &Path,
+    diagnostics: Vec<Diagnostic>,
+    config: &Config,
+) -> Result<Vec<Diagnostic>> {
+    let Some(content) = Git::show_file(rev, path)? else {
+        return Ok(diagnostics);
+    };
+    let old = EnvFile::parse_content_with_dialect(
+        path.to_path_buf(),
+        &content,
+        config.dialect_for(path),
+    )?;
+
+    let mut baseline: HashMap<(RuleId, String), usize> = HashMap::new();
+    for d in config.apply(check_file_with_config(&old, config)) {
+        *baseline.entry((d.id, d.message)).or_default() += 1;
     }
+
+    Ok(diagnostics
+        .into_iter()
+        .filter(
+            |d| match baseline.get_mut(&(d.id.clone(), d.message.clone())) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                },
+                _ => true,
+            },
+        )
+        .collect())
 }
@@ -3,6 +3,7 @@ pub mod ansible;
 pub mod argo;
 pub mod compare;
 pub mod completions;
+pub mod diff;
 pub mod doctor;
 pub mod fix;
 pub mod helm;
@@ -12,33 +13,93 @@ pub mod terraform;
 pub mod tui;
 
 use clap::{Args, Subcommand};
+use std::io;
 use std::path::PathBuf;
 
+use crate::config::Config;
+use crate::error::{EnvCheckError, Result};
+use crate::output::{write_report, Format, Sources};
+use crate::rules::{Diagnostic, Severity};
+
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Lint .env files
     Lint {
         #[arg(required = true)]
         files: Vec<PathBuf>,
+
+        /// Only report problems that the files did not have at this git revision
+        #[arg(long, value_name = "REV")]
+        git_ref: Option<String>,
     },
-    /// Fix .env files (sort keys, remove whitespace)
+    /// Fix .env files (apply rule fixes, then sort keys)
     Fix {
         #[arg(required = true)]
         files: Vec<PathBuf>,
 
-        /// Auto-commit fixes to current branch
+        /// Commit the changed files on a new branch (see `fix.branch`)
         #[arg(long)]
         commit: bool,
 
-        /// Create a PR with fixes (requires gh CLI)
+        /// Commit on a new branch, push it and open a PR (see `fix.pr_command`)
         #[arg(long)]
         pr: bool,
+
+        /// Exit with an error if any file would change, without writing
+        #[arg(long, conflicts_with_all = ["commit", "pr"])]
+        check: bool,
+
+        /// Print a unified diff of the changes instead of writing
+        #[arg(long, conflicts_with_all = ["commit", "pr"])]
+        diff: bool,
+
+        /// Print the fixed content instead of writing
+        #[arg(long, conflicts_with_all = ["commit", "pr"])]
+        stdout: bool,
     },
     /// Compare .env files
     Compare {
-        /// Reference file (e.g. .env.example)
-        #[arg(required = true, num_args = 2..)]
+        /// Reference file (e.g. .env.example), then the files to compare with it.
+        /// With `--git-ref`, each file is compared with its own older version.
+        #[arg(required = true)]
         files: Vec<PathBuf>,
+
+        /// Show the keys added, removed or renamed since this git revision
+        #[arg(long, value_name = "REV", conflicts_with_all = ["mode", "values"])]
+        git_ref: Option<String>,
+
+        /// reference: first file against the rest; symmetric: also report extra
+        /// keys (W017); matrix: print which files define each key
+        #[arg(long, value_enum, default_value = "reference")]
+        mode: compare::CompareMode,
+
+        /// Also report keys whose values differ between files (W019)
+        #[arg(long)]
+        values: bool,
+
+        /// How values are shown in messages [default: config or hash]
+        #[arg(long, value_enum)]
+        redact: Option<crate::rules::values::Redaction>,
+    },
+
+    /// Show the key-level difference between two .env files
+    Diff {
+        /// Old and new file. Also accepts the arguments git passes to an external
+        /// diff driver, or a single file with `--textconv`.
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Only report required keys that were removed or renamed, and fail if any
+        #[arg(long)]
+        breaking: bool,
+
+        /// Print the file's keys with redacted values, for a git `textconv` filter
+        #[arg(long, conflicts_with = "breaking")]
+        textconv: bool,
+
+        /// How values are shown [default: config or hash]
+        #[arg(long, value_enum)]
+        redact: Option<crate::rules::values::Redaction>,
     },
 
     /// Detect mismatches between K8s manifests and .env files
@@ -140,3 +201,36 @@ pub struct ArgoArgs {
     #[arg(long, short, default_value = ".env")]
     pub env: PathBuf,
 }
+
+/// Writes diagnostics to stdout and fails with [`EnvCheckError::LintFailed`]
+/// if any of them is an error.
+pub(crate) fn report(
+    diagnostics: &[Diagnostic],
+    format: Format,
+    config: &Config,
+    sources: &Sources,
+) -> Result<()> {
+    let stdout = io::stdout();
+    let mut handle = stdout.lock();
+    let sources = (!config.compact).then_some(sources);
+    write_report(format, diagnostics, sources, &mut handle)
+        .map_err(|e| EnvCheckError::read_error("stdout", e))?;
+
+    let error_count = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warning_count = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .count();
+
+    if error_count > 0 {
+        Err(EnvCheckError::LintFailed {
+            error_count,
+            warning_count,
+        })
+    } else {
+        Ok(())
+    }
+}
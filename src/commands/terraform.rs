@@ -1,16 +1,18 @@
+use crate::config::Config;
 use crate::error::Result;
 use crate::output::Format;
 use crate::parser::{env, terraform};
 use colored::*;
 use std::path::Path;
 
-pub fn run(dir: &Path, env_path: &Path, _format: Format) -> Result<()> {
+pub fn run(dir: &Path, env_path: &Path, _format: Format, config: &Config) -> Result<()> {
     // 1. Parse Terraform directory
     let tf_vars = terraform::parse_directory(dir)?;
 
     // 2. Parse .env file
-    let env_file = env::EnvFile::parse(env_path)?;
-    let env_entries = env_file.vars;
+    let env_file = env::EnvFile::parse_with_dialect(env_path, config.dialect_for(env_path))?;
+    // Keys marked optional may be missing
+    let optional = env_file.optional_keys();
 
     // 3. Compare
     // Terraform `variable "foo"` expects `TF_VAR_foo` in environment (if generic)
@@ -21,16 +23,9 @@ pub fn run(dir: &Path, env_path: &Path, _format: Format) -> Result<()> {
 
     for tf_var in &tf_vars {
         let expected_env_key = format!("TF_VAR_{}", tf_var.name);
-        // Check if ANY key in .env matches this
-        let mut found = false;
-        for entry in &env_entries {
-            if entry.key == expected_env_key {
-                found = true;
-                break;
-            }
-        }
+        let found = env_file.resolved_value(&expected_env_key).is_some();
 
-        if !found {
+        if !found && !config.is_optional(&expected_env_key, &optional) {
             missing_in_env.push((tf_var, expected_env_key));
         }
     }
@@ -1,6 +1,20 @@
 use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use crate::error::{EnvCheckError, Result};
+use crate::output::Format;
+use crate::parser::Dialect;
+use crate::parser::EnvFile;
+use crate::rules::custom::CustomRule;
+use crate::rules::naming::NamingStyle;
+use crate::rules::placeholder::PlaceholderPatterns;
+use crate::rules::secrets::SecretPatterns;
+use crate::rules::sort::SortOrder;
+use crate::rules::values::Redaction;
+use crate::rules::{Diagnostic, Severity};
+use crate::schema::EnvSchema;
 
 /// Configuration for envcheck, loaded from `.envcheckrc.yaml` or `.envcheckrc.toml`
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -12,8 +26,62 @@ pub struct Config {
     /// Ignore patterns
     pub ignore: Vec<String>,
 
+    /// Key globs that may be missing from an environment
+    pub optional: Vec<String>,
+
     /// Default output format
     pub format: Option<String>,
+
+    /// Print text output without source snippets
+    pub compact: bool,
+
+    /// Dialect used to parse .env files
+    pub dialect: Option<Dialect>,
+
+    /// Per-file dialects, keyed by path pattern (e.g., `"docker/*.env": docker`)
+    pub dialects: BTreeMap<String, Dialect>,
+
+    /// Secret detection settings
+    pub secrets: SecretsConfig,
+
+    /// Key naming convention
+    pub naming: NamingConfig,
+
+    /// Key order for W003 and `fix`
+    pub sort: SortConfig,
+
+    /// Value checks across environments for `compare`
+    pub compare: CompareConfig,
+
+    /// `envcheck fix` settings
+    pub fix: FixConfig,
+
+    /// Production file classification and placeholder detection
+    pub production: ProductionConfig,
+
+    /// Path to an env schema file, relative to the configuration
+    pub schema: Option<PathBuf>,
+
+    /// The loaded env schema, see [`Config::load_schema`]
+    #[serde(skip)]
+    pub env_schema: Option<EnvSchema>,
+
+    /// The compiled `secrets` allowlists, see [`Config::compile_rules`]
+    #[serde(skip)]
+    pub secret_patterns: SecretPatterns,
+
+    /// The compiled `production` patterns, see [`Config::compile_rules`]
+    #[serde(skip)]
+    pub placeholder_patterns: PlaceholderPatterns,
+
+    /// The compiled `rules.custom`, see [`Config::compile_rules`]
+    #[serde(skip)]
+    pub custom_rules: Vec<CustomRule>,
+
+    /// Directory the configuration (or `.envcheckignore`) was found in.
+    /// Ignore patterns are matched relative to it.
+    #[serde(skip)]
+    pub root: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize)]
@@ -27,46 +95,298 @@ pub struct RulesConfig {
 
     /// Per-rule severity overrides
     pub severity: std::collections::HashMap<String, String>,
+
+    /// Organization-specific rules
+    pub custom: Vec<CustomRuleConfig>,
+}
+
+/// A user-defined rule matching keys by glob and values by regex.
+///
+/// Without `must_match` or `must_not_match`, every matching key is reported.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CustomRuleConfig {
+    /// Rule ID used in output, `disable` and suppression comments (e.g., "ORG001")
+    pub id: String,
+
+    /// Severity of findings: error, warning or info [default: warning]
+    pub severity: Option<String>,
+
+    /// Glob pattern for keys the rule applies to [default: all keys]
+    pub key: Option<String>,
+
+    /// Regex the value must match
+    pub must_match: Option<String>,
+
+    /// Regex the value must not match
+    pub must_not_match: Option<String>,
+
+    /// Message for findings; `{key}` is replaced by the key
+    pub message: Option<String>,
+
+    /// Glob patterns for files the rule applies to [default: all files]
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    /// Regexes for values that are known not to be secrets
+    pub allow: Vec<String>,
+
+    /// Glob patterns for keys whose values are never checked
+    pub allow_keys: Vec<String>,
+
+    /// Minimum Shannon entropy (bits per character) for a value to count as a secret
+    pub entropy_threshold: f64,
+
+    /// Minimum length of a value checked for entropy
+    pub min_length: usize,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            allow: Vec::new(),
+            allow_keys: Vec::new(),
+            entropy_threshold: 4.0,
+            min_length: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FixConfig {
+    /// Remove `export` from every key
+    pub remove_export: bool,
+
+    /// Commit message for `fix --commit` and `fix --pr`
+    pub commit_message: String,
+
+    /// Branch the fixes are committed on; `{timestamp}` becomes the Unix time
+    pub branch: String,
+
+    /// Remote the branch is pushed to before opening a PR
+    pub remote: String,
+
+    /// Command that opens the PR; `{branch}` and `{message}` are substituted
+    pub pr_command: Vec<String>,
+}
+
+impl Default for FixConfig {
+    fn default() -> Self {
+        Self {
+            remove_export: false,
+            commit_message: "fix: auto-fix .env files via envcheck".to_string(),
+            branch: "envcheck/fix-{timestamp}".to_string(),
+            remote: "origin".to_string(),
+            pr_command: ["gh", "pr", "create", "--fill", "--head", "{branch}"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SortConfig {
+    /// alphabetical, case-insensitive, natural, prefix or reference
+    pub order: SortOrder,
+
+    /// File whose key order `reference` follows (default: `.env.example`)
+    pub reference: Option<PathBuf>,
+
+    /// Keys of the reference file, in order
+    #[serde(skip)]
+    pub reference_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CompareConfig {
+    /// How values are shown: hash, length, mask or none
+    pub redact: Redaction,
+
+    /// Key globs whose values must differ between environments (secrets)
+    pub must_differ: Vec<String>,
+
+    /// Key globs whose values must match across environments (feature flags)
+    pub must_equal: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NamingConfig {
+    /// Key style: screaming-snake, snake or any
+    pub style: NamingStyle,
+
+    /// Regex keys must match, used instead of `style`
+    pub pattern: Option<String>,
+
+    /// Allowed key prefixes by file pattern (e.g., `".env.app": [APP_, DB_]`)
+    pub prefixes: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ProductionConfig {
+    /// Glob patterns for files deployed to production
+    pub files: Vec<String>,
+
+    /// Case-insensitive regexes for placeholder values
+    pub placeholders: Vec<String>,
+
+    /// Unsafe values by key glob, as case-insensitive regexes (e.g., `DEBUG: "^true$"`)
+    pub unsafe_values: BTreeMap<String, String>,
+}
+
+impl Default for ProductionConfig {
+    fn default() -> Self {
+        Self {
+            files: vec![".env.production".to_string(), ".env.prod*".to_string()],
+            placeholders: [
+                r"^(change[_-]?me|replace[_-]?me|todo|tbd|fixme|placeholder|dummy|x{3,})$",
+                r"^<[^<>]+>$",
+                r"^your[_-]",
+                r"^(password|secret|admin)$",
+                r"\bexample\.(com|org|net)\b",
+                r"\b(localhost|127\.0\.0\.1)\b",
+            ]
+            .map(String::from)
+            .to_vec(),
+            unsafe_values: BTreeMap::from([(
+                "DEBUG".to_string(),
+                r"^(true|1|yes|on)$".to_string(),
+            )]),
+        }
+    }
 }
 
 impl Config {
     /// Load configuration from the current directory or parents
-    #[must_use]
-    pub fn load() -> Self {
+    pub fn load() -> Result<Self> {
         Self::load_from_path(Path::new("."))
     }
 
-    /// Load configuration from a specific path
-    #[must_use]
-    pub fn load_from_path(start: &Path) -> Self {
+    /// Load configuration from a specific path, merging in `.envcheckignore` patterns.
+    /// Fails if the nearest config file cannot be read or parsed.
+    pub fn load_from_path(start: &Path) -> Result<Self> {
+        let mut config = Self::find_config(start)?;
+        let ignore_root = Self::find_upwards(start, ".envcheckignore");
+        config.ignore.extend(Self::load_ignore_file(start));
+        if config.root.is_none() {
+            config.root = ignore_root;
+        }
+        Ok(config)
+    }
+
+    fn find_config(start: &Path) -> Result<Self> {
         let mut current = start.canonicalize().ok();
 
         while let Some(dir) = current {
-            // Try YAML first
+            // Try YAML first, then TOML
             let yaml_path = dir.join(".envcheckrc.yaml");
-            if yaml_path.exists() {
-                if let Ok(content) = fs::read_to_string(&yaml_path) {
-                    if let Ok(config) = serde_yaml::from_str(&content) {
-                        return config;
-                    }
-                }
-            }
-
-            // Try TOML
             let toml_path = dir.join(".envcheckrc.toml");
-            if toml_path.exists() {
-                if let Ok(content) = fs::read_to_string(&toml_path) {
-                    if let Ok(config) = toml::from_str(&content) {
-                        return config;
-                    }
-                }
+            let parsed =
+                if yaml_path.exists() {
+                    let content = fs::read_to_string(&yaml_path)
+                        .map_err(|e| EnvCheckError::read_error(&yaml_path, e))?;
+                    Some(serde_yaml::from_str::<Self>(&content).map_err(|e| {
+                        EnvCheckError::invalid_config(yaml_path.display().to_string(), e)
+                    }))
+                } else if toml_path.exists() {
+                    let content = fs::read_to_string(&toml_path)
+                        .map_err(|e| EnvCheckError::read_error(&toml_path, e))?;
+                    Some(toml::from_str::<Self>(&content).map_err(|e| {
+                        EnvCheckError::invalid_config(toml_path.display().to_string(), e)
+                    }))
+                } else {
+                    None
+                };
+
+            if let Some(config) = parsed {
+                return Ok(Self {
+                    root: Some(dir),
+                    ..config?
+                });
             }
 
             // Move to parent
             current = dir.parent().map(Path::to_path_buf);
         }
 
-        Self::default()
+        Ok(Self::default())
+    }
+
+    /// Loads the env schema from `path`, the configured `schema`, or the nearest
+    /// `envcheck.schema.{toml,yaml,yml}`, in that order.
+    pub fn load_schema(&mut self, path: Option<&Path>) -> Result<()> {
+        let path = match (path, &self.schema) {
+            (Some(path), _) => Some(path.to_path_buf()),
+            (None, Some(configured)) => Some(
+                self.root
+                    .as_ref()
+                    .map_or_else(|| configured.clone(), |root| root.join(configured)),
+            ),
+            (None, None) => EnvSchema::find(Path::new(".")),
+        };
+
+        self.env_schema = path.as_deref().map(EnvSchema::load).transpose()?;
+        Ok(())
+    }
+
+    /// Compiles the custom rules and the secret and placeholder patterns once,
+    /// and checks the other patterns of the built-in rule settings, failing on
+    /// the first invalid one.
+    pub fn compile_rules(&mut self) -> Result<()> {
+        for pattern in &self.compare.must_differ {
+            check_glob("compare.must_differ", pattern)?;
+        }
+        for pattern in &self.compare.must_equal {
+            check_glob("compare.must_equal", pattern)?;
+        }
+        if let Some(pattern) = &self.naming.pattern {
+            check_regex("naming.pattern", pattern)?;
+        }
+
+        self.secret_patterns = SecretPatterns::new(&self.secrets)?;
+        self.placeholder_patterns = PlaceholderPatterns::new(&self.production)?;
+        self.custom_rules = self
+            .rules
+            .custom
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| CustomRule::new(i, rule))
+            .collect::<Result<_>>()?;
+        Ok(())
+    }
+
+    /// Reads the keys of the reference file when `sort.order` is `reference`.
+    /// A relative path is resolved against the config directory.
+    pub fn load_sort_reference(&mut self) -> Result<()> {
+        if self.sort.order != SortOrder::Reference {
+            return Ok(());
+        }
+
+        let reference = self
+            .sort
+            .reference
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(".env.example"));
+        let path = match &self.root {
+            Some(root) if reference.is_relative() => root.join(reference),
+            _ => reference,
+        };
+        let env_file = EnvFile::parse(path)?;
+        self.sort.reference_keys = env_file.vars.into_iter().map(|var| var.key).collect();
+        Ok(())
+    }
+
+    /// Returns true if `path` is classified as a production file
+    #[must_use]
+    pub fn is_production(&self, path: &Path) -> bool {
+        self.matches(path, &self.production.files)
     }
 
     /// Check if a rule is disabled
@@ -75,6 +395,114 @@ impl Config {
         self.rules.disable.iter().any(|r| r == rule_id)
     }
 
+    /// Returns the configured default output format, if it is a valid one
+    #[must_use]
+    pub fn output_format(&self) -> Option<Format> {
+        self.format.as_deref().and_then(|f| f.parse().ok())
+    }
+
+    /// Returns the effective severity of a rule after overrides and `warnings_as_errors`
+    #[must_use]
+    pub fn severity_for(&self, rule_id: &str, default: Severity) -> Severity {
+        let severity = self
+            .rules
+            .severity
+            .get(rule_id)
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(default);
+
+        if self.rules.warnings_as_errors && severity == Severity::Warning {
+            Severity::Error
+        } else {
+            severity
+        }
+    }
+
+    /// Check if a path matches the configured ignore patterns
+    #[must_use]
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        self.matches(path, &self.ignore)
+    }
+
+    /// Returns the dialect to parse a file with.
+    ///
+    /// The longest matching pattern in `dialects` wins, then `dialect`, then dotenv.
+    #[must_use]
+    pub fn dialect_for(&self, path: &Path) -> Dialect {
+        self.dialects
+            .iter()
+            .filter(|(pattern, _)| self.matches(path, std::slice::from_ref(*pattern)))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, dialect)| *dialect)
+            .or(self.dialect)
+            .unwrap_or_default()
+    }
+
+    /// Returns the allowed key prefixes for a file; the longest matching pattern wins.
+    #[must_use]
+    pub fn prefixes_for(&self, path: &Path) -> &[String] {
+        self.naming
+            .prefixes
+            .iter()
+            .filter(|(pattern, _)| self.matches(path, std::slice::from_ref(*pattern)))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map_or(&[], |(_, prefixes)| prefixes.as_slice())
+    }
+
+    /// Returns true if `key` may be missing: it is among the keys the reference
+    /// file `marked` optional, or matches a glob under `optional`.
+    #[must_use]
+    pub fn is_optional(&self, key: &str, marked: &BTreeSet<String>) -> bool {
+        marked.contains(key)
+            || self
+                .optional
+                .iter()
+                .any(|p| glob::Pattern::new(p).is_ok_and(|p| p.matches(key)))
+    }
+
+    pub(crate) fn matches(&self, path: &Path, patterns: &[String]) -> bool {
+        if Self::should_ignore(path, patterns) {
+            return true;
+        }
+
+        // Also match relative to the directory the config lives in, so that patterns
+        // like `tests/fixtures/**` work regardless of how the path was passed.
+        let relative = self.root.as_ref().and_then(|root| {
+            let absolute = path.canonicalize().ok()?;
+            absolute.strip_prefix(root).ok().map(Path::to_path_buf)
+        });
+        relative.is_some_and(|rel| Self::should_ignore(&rel, patterns))
+    }
+
+    /// Applies the configuration to diagnostics produced by a command.
+    ///
+    /// Diagnostics for disabled rules or ignored paths are dropped, and the
+    /// severity of the remaining ones is remapped.
+    #[must_use]
+    pub fn apply(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter(|d| !self.is_rule_disabled(&d.id.to_string()) && !self.is_ignored(&d.path))
+            .map(|mut d| {
+                d.severity = self.severity_for(&d.id.to_string(), d.severity);
+                d
+            })
+            .collect()
+    }
+
+    fn find_upwards(start: &Path, file_name: &str) -> Option<PathBuf> {
+        let mut current = start.canonicalize().ok();
+
+        while let Some(dir) = current {
+            if dir.join(file_name).exists() {
+                return Some(dir);
+            }
+            current = dir.parent().map(Path::to_path_buf);
+        }
+
+        None
+    }
+
     /// Load ignore patterns from .envcheckignore file
     #[must_use]
     pub fn load_ignore_file(start: &Path) -> Vec<String> {
@@ -107,6 +535,12 @@ impl Config {
             .unwrap_or_default();
 
         for pattern in patterns {
+            if let Ok(glob) = glob::Pattern::new(pattern) {
+                if glob.matches(&path_str) || glob.matches(&file_name) {
+                    return true;
+                }
+            }
+
             // Simple glob matching
             if let Some(suffix) = pattern.strip_prefix('*') {
                 if path_str.ends_with(suffix) || file_name.ends_with(suffix) {
@@ -126,9 +560,38 @@ impl Config {
     }
 }
 
+/// Fails with an error naming `setting` if `pattern` is not a valid regex.
+fn check_regex(setting: &str, pattern: &str) -> Result<()> {
+    regex::Regex::new(pattern)
+        .map(drop)
+        .map_err(|e| EnvCheckError::invalid_config(setting, e))
+}
+
+/// Fails with an error naming `setting` if `pattern` is not a valid glob.
+fn check_glob(setting: &str, pattern: &str) -> Result<()> {
+    glob::Pattern::new(pattern)
+        .map(drop)
+        .map_err(|e| EnvCheckError::invalid_config(setting, e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rules::RuleId;
+
+    fn diagnostic(id: RuleId, severity: Severity, path: &str) -> Diagnostic {
+        Diagnostic {
+            id,
+            severity,
+            message: String::new(),
+            path: PathBuf::from(path),
+            line: Some(1),
+            span: None,
+            labels: Vec::new(),
+            constraint: None,
+            fix: None,
+        }
+    }
 
     #[test]
     fn test_default_config() {
@@ -172,4 +635,96 @@ warnings_as_errors = false
         assert_eq!(config.rules.disable, vec!["E001"]);
         assert_eq!(config.format, Some("sarif".to_string()));
     }
+
+    #[test]
+    fn test_apply_disables_and_remaps() {
+        let yaml = r#"
+rules:
+  disable: [W003]
+  severity:
+    W001: error
+    E001: info
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        let diagnostics = config.apply(vec![
+            diagnostic(RuleId::W003, Severity::Warning, ".env"),
+            diagnostic(RuleId::W001, Severity::Warning, ".env"),
+            diagnostic(RuleId::E001, Severity::Error, ".env"),
+        ]);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].id, RuleId::W001);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[1].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_warnings_as_errors() {
+        let mut config = Config::default();
+        config.rules.warnings_as_errors = true;
+
+        assert_eq!(
+            config.severity_for("W001", Severity::Warning),
+            Severity::Error
+        );
+        assert_eq!(config.severity_for("W006", Severity::Info), Severity::Info);
+    }
+
+    #[test]
+    fn test_ignored_paths_are_dropped() {
+        let config = Config {
+            ignore: vec!["*.local".to_string(), "fixtures/**".to_string()],
+            ..Config::default()
+        };
+
+        assert!(config.is_ignored(Path::new(".env.local")));
+        assert!(config.is_ignored(Path::new("fixtures/env/a.env")));
+        assert!(!config.is_ignored(Path::new(".env")));
+
+        let diagnostics = config.apply(vec![diagnostic(
+            RuleId::W001,
+            Severity::Warning,
+            ".env.local",
+        )]);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_output_format() {
+        let config = Config {
+            format: Some("json".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(config.output_format(), Some(Format::Json));
+
+        let config = Config {
+            format: Some("bogus".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(config.output_format(), None);
+    }
+
+    #[test]
+    fn test_dialect_for() {
+        let yaml = r#"
+dialect: node-dotenv
+dialects:
+  "*.env": compose
+  "docker/*.env": docker
+"#;
+        let config: Config = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            config.dialect_for(Path::new("docker/app.env")),
+            Dialect::Docker
+        );
+        assert_eq!(config.dialect_for(Path::new("app.env")), Dialect::Compose);
+        assert_eq!(
+            config.dialect_for(Path::new(".env.local")),
+            Dialect::NodeDotenv
+        );
+        assert_eq!(
+            Config::default().dialect_for(Path::new(".env")),
+            Dialect::Dotenv
+        );
+    }
 }
@@ -0,0 +1,346 @@
+//! Key-level changes between two versions of a .env file.
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::parser::env::{NodeKind, TokenKind};
+use crate::parser::{EnvFile, EnvVar};
+use crate::rules::values::Redaction;
+
+/// A key that was renamed between versions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Keys added, removed or renamed between an old and a new version of a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct KeyChanges {
+    pub path: PathBuf,
+    /// Where the old version came from, such as a git revision.
+    pub from: String,
+    /// Where the new version came from, such as "working copy".
+    pub to: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub renamed: Vec<Rename>,
+}
+
+impl KeyChanges {
+    /// Compares the keys of two versions of a file.
+    ///
+    /// A removed and an added key count as a rename if they have the same
+    /// non-empty value, or else if they sit after the same unchanged key and
+    /// share their first or last word, like `DB_URL` and `DATABASE_URL`.
+    #[must_use]
+    pub fn between(old: &EnvFile, new: &EnvFile) -> Self {
+        let old_vars = unique(old);
+        let new_vars = unique(new);
+        let old_keys: HashSet<&str> = old_vars.iter().map(|v| v.key.as_str()).collect();
+        let new_keys: HashSet<&str> = new_vars.iter().map(|v| v.key.as_str()).collect();
+
+        let mut removed: Vec<&EnvVar> = old_vars
+            .iter()
+            .copied()
+            .filter(|v| !new_keys.contains(v.key.as_str()))
+            .collect();
+        let mut added: Vec<&EnvVar> = new_vars
+            .iter()
+            .copied()
+            .filter(|v| !old_keys.contains(v.key.as_str()))
+            .collect();
+
+        let old_anchors = anchors(&old_vars, &new_keys);
+        let new_anchors = anchors(&new_vars, &old_keys);
+        let mut renamed = Vec::new();
+        pair(&mut removed, &mut added, &mut renamed, |r, a| {
+            !r.value.is_empty() && r.value == a.value
+        });
+        pair(&mut removed, &mut added, &mut renamed, |r, a| {
+            old_anchors[r.key.as_str()] == new_anchors[a.key.as_str()] && similar(&r.key, &a.key)
+        });
+
+        Self {
+            path: new.path.clone(),
+            added: added.iter().map(|v| v.key.clone()).collect(),
+            removed: removed.iter().map(|v| v.key.clone()).collect(),
+            renamed,
+            ..Self::default()
+        }
+    }
+
+    /// Names the two versions being compared.
+    #[must_use]
+    pub fn labelled(self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            ..self
+        }
+    }
+
+    /// Returns true if no key was added, removed or renamed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.renamed.is_empty()
+    }
+}
+
+/// A key whose value changed, with both values redacted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValueChange {
+    pub key: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// The semantic difference between two .env files, as shown by `envcheck diff`.
+///
+/// Quoting, spacing and `export` prefixes are ignored: only keys, effective
+/// values, key order and comment text count.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EnvDiff {
+    #[serde(flatten)]
+    pub keys: KeyChanges,
+    pub changed: Vec<ValueChange>,
+    /// The keys both files share appear in a different order.
+    pub reordered: bool,
+    /// Comments were added, removed or edited.
+    pub comments_changed: bool,
+    /// Required keys of the old file that the new file no longer defines,
+    /// including renamed ones.
+    pub breaking: Vec<String>,
+}
+
+impl EnvDiff {
+    /// Compares two files. Keys are required unless the old file or the
+    /// config marks them optional.
+    #[must_use]
+    pub fn between(old: &EnvFile, new: &EnvFile, redaction: Redaction, config: &Config) -> Self {
+        let keys = KeyChanges::between(old, new);
+
+        let new_values: HashMap<&str, &str> = effective_values(new).into_iter().collect();
+        let changed = effective_values(old)
+            .into_iter()
+            .filter_map(|(key, from)| {
+                let to = new_values.get(key)?;
+                (from != *to).then(|| ValueChange {
+                    key: key.to_string(),
+                    from: redaction.apply(from),
+                    to: redaction.apply(to),
+                })
+            })
+            .collect();
+
+        let shared_order = |file: &EnvFile, other: &EnvFile| -> Vec<String> {
+            let keys: HashSet<&str> = other.vars.iter().map(|v| v.key.as_str()).collect();
+            unique(file)
+                .into_iter()
+                .filter(|v| keys.contains(v.key.as_str()))
+                .map(|v| v.key.clone())
+                .collect()
+        };
+
+        let optional = old.optional_keys();
+        let breaking = keys
+            .removed
+            .iter()
+            .chain(keys.renamed.iter().map(|r| &r.from))
+            .filter(|key| !config.is_optional(key, &optional))
+            .cloned()
+            .collect();
+
+        Self {
+            reordered: shared_order(old, new) != shared_order(new, old),
+            comments_changed: comments(old) != comments(new),
+            keys,
+            changed,
+            breaking,
+        }
+    }
+
+    /// Names the two files being compared.
+    #[must_use]
+    pub fn labelled(self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            keys: self.keys.labelled(from, to),
+            ..self
+        }
+    }
+
+    /// Returns true if the files are equivalent.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty() && self.changed.is_empty() && !self.reordered && !self.comments_changed
+    }
+}
+
+/// The effective (last) value of each key with references expanded, as `compare`
+/// sees it, in the order keys first appear.
+fn effective_values(env_file: &EnvFile) -> Vec<(&str, &str)> {
+    unique(env_file)
+        .into_iter()
+        .map(|v| {
+            let value = env_file.resolved_value(&v.key).unwrap_or_default();
+            (v.key.as_str(), value)
+        })
+        .collect()
+}
+
+/// The text of every comment, whole-line or inline, without surrounding spaces.
+fn comments(env_file: &EnvFile) -> Vec<&str> {
+    let tree = &env_file.syntax;
+    tree.nodes()
+        .iter()
+        .filter_map(|node| match node.kind {
+            NodeKind::Comment => Some(tree.node_text(node)),
+            NodeKind::Entry => node
+                .token(TokenKind::InlineComment)
+                .map(|t| tree.token_text(t)),
+            NodeKind::Blank | NodeKind::Invalid => None,
+        })
+        .map(str::trim)
+        .collect()
+}
+
+/// Moves each removed key with a matching added key into `renamed`.
+fn pair(
+    removed: &mut Vec<&EnvVar>,
+    added: &mut Vec<&EnvVar>,
+    renamed: &mut Vec<Rename>,
+    matches: impl Fn(&EnvVar, &EnvVar) -> bool,
+) {
+    removed.retain(|r| {
+        let Some(i) = added.iter().position(|a| matches(r, a)) else {
+            return true;
+        };
+        let a = added.remove(i);
+        renamed.push(Rename {
+            from: r.key.clone(),
+            to: a.key.clone(),
+        });
+        false
+    });
+}
+
+/// Returns true if two keys share their first or last `_`-separated word.
+fn similar(a: &str, b: &str) -> bool {
+    let words = |key: &str| {
+        let key = key.trim_matches('_').to_ascii_uppercase();
+        let first = key.split('_').next().unwrap_or_default().to_string();
+        let last = key.rsplit('_').next().unwrap_or_default().to_string();
+        (first, last)
+    };
+    let (a_first, a_last) = words(a);
+    let (b_first, b_last) = words(b);
+    (!a_first.is_empty() && a_first == b_first) || (!a_last.is_empty() && a_last == b_last)
+}
+
+/// The first definition of each key, in file order.
+fn unique(env_file: &EnvFile) -> Vec<&EnvVar> {
+    let mut seen = HashSet::new();
+    env_file
+        .vars
+        .iter()
+        .filter(|v| seen.insert(v.key.as_str()))
+        .collect()
+}
+
+/// Maps each key to the closest key before it that both versions share.
+fn anchors<'a>(vars: &[&'a EnvVar], shared: &HashSet<&str>) -> HashMap<&'a str, Option<&'a str>> {
+    let mut anchor = None;
+    let mut anchors = HashMap::new();
+    for var in vars {
+        anchors.insert(var.key.as_str(), anchor);
+        if shared.contains(var.key.as_str()) {
+            anchor = Some(var.key.as_str());
+        }
+    }
+    anchors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_file(content: &str) -> EnvFile {
+        EnvFile::parse_content(PathBuf::from(".env"), content).unwrap()
+    }
+
+    #[test]
+    fn test_added_and_removed() {
+        let changes = KeyChanges::between(&env_file("A=1\nB=2\n"), &env_file("B=2\nC=\n"));
+        assert!(changes.renamed.is_empty());
+        assert_eq!(changes.removed, ["A"]);
+        assert_eq!(changes.added, ["C"]);
+    }
+
+    #[test]
+    fn test_renames_by_value_then_position() {
+        let old = env_file("DB_URL=postgres://db\nPORT=\nHOST=\nLOG=\n");
+        let new = env_file("PORT=\nAPP_HOST=\nDATABASE_URL=postgres://db\nLOG=\nEXTRA=\n");
+
+        let changes = KeyChanges::between(&old, &new);
+        assert_eq!(
+            changes.renamed,
+            [
+                Rename {
+                    from: "DB_URL".to_string(),
+                    to: "DATABASE_URL".to_string()
+                },
+                Rename {
+                    from: "HOST".to_string(),
+                    to: "APP_HOST".to_string()
+                },
+            ]
+        );
+        assert_eq!(changes.added, ["EXTRA"]);
+        assert!(changes.removed.is_empty());
+        assert!(!changes.is_empty());
+    }
+
+    #[test]
+    fn test_unrelated_keys_in_the_same_place_are_not_renames() {
+        let old = env_file("PORT=\nLEGACY_FLAG=\nLOG=\n");
+        let new = env_file("PORT=\nSENTRY_DSN=\nLOG=\n");
+
+        let changes = KeyChanges::between(&old, &new);
+        assert!(changes.renamed.is_empty());
+        assert_eq!(changes.removed, ["LEGACY_FLAG"]);
+        assert_eq!(changes.added, ["SENTRY_DSN"]);
+    }
+
+    #[test]
+    fn test_env_diff_ignores_formatting() {
+        let old = env_file("# Database\nA=1\nB='two'\nC=${A}/x\n");
+        let new = env_file("# Database\n\nexport A=\"1\"\nB=two\nC=1/x\n");
+        let diff = EnvDiff::between(&old, &new, Redaction::None, &Config::default());
+        assert!(diff.is_empty(), "{diff:?}");
+    }
+
+    #[test]
+    fn test_env_diff_reports_values_order_comments_and_breaking() {
+        let old = env_file("A=1\nB=2\n# @optional\nC=\nD=\n");
+        let new = env_file("B=3\nA=1 # now inline\n");
+        let diff = EnvDiff::between(&old, &new, Redaction::None, &Config::default());
+        assert_eq!(
+            diff.changed,
+            [ValueChange {
+                key: "B".to_string(),
+                from: "`2`".to_string(),
+                to: "`3`".to_string()
+            }]
+        );
+        assert!(diff.reordered);
+        assert!(diff.comments_changed);
+        assert_eq!(diff.keys.removed, ["C", "D"]);
+        assert_eq!(diff.breaking, ["D"]);
+
+        let diff = EnvDiff::between(&old, &new, Redaction::Hash, &Config::default());
+        assert_eq!(diff.changed[0].from, "hash:d4735e3a");
+    }
+}
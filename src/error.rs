@@ -61,6 +61,15 @@ pub enum EnvCheckError {
         source: serde_yaml::Error,
     },
 
+    /// Failed to load an env schema file.
+    #[error("invalid schema '{path}': {message}")]
+    SchemaError {
+        /// Path of the schema file.
+        path: PathBuf,
+        /// Description of the problem.
+        message: String,
+    },
+
     /// Invalid glob pattern.
     #[error("invalid glob pattern '{pattern}': {source}")]
     GlobError {
@@ -71,6 +80,15 @@ pub enum EnvCheckError {
         source: glob::PatternError,
     },
 
+    /// A setting in `.envcheckrc` has an invalid value.
+    #[error("invalid config `{setting}`: {message}")]
+    InvalidConfig {
+        /// The setting, such as `rules.custom[ORG001].must_match`.
+        setting: String,
+        /// Why the value is invalid.
+        message: String,
+    },
+
     /// No files matched the provided pattern or arguments.
     #[error("no files matched: {pattern}")]
     NoFilesMatched {
@@ -85,8 +103,17 @@ pub enum EnvCheckError {
         format: String,
     },
 
-    /// Compare command requires at least two files.
-    #[error("compare requires at least two files, got {count}")]
+    /// The output only exists as text, JSON or markdown, not as diagnostics.
+    #[error("{output} cannot be written as {format}; use text, json or markdown")]
+    UnsupportedFormat {
+        /// What the command prints, such as "the key matrix".
+        output: String,
+        /// The requested format.
+        format: String,
+    },
+
+    /// Compare command requires at least two files, unless comparing with a git revision.
+    #[error("compare: at least 2 files required (or 1 with --git-ref), got {count}")]
     InsufficientFiles {
         /// Number of files provided.
         count: usize,
@@ -96,6 +123,52 @@ pub enum EnvCheckError {
     #[error("k8s-sync requires --env flag to specify the .env file to compare against")]
     MissingEnvFile,
 
+    /// `fix --check` found files that would change.
+    #[error("{count} file(s) would be changed by fix")]
+    FixNeeded {
+        /// Number of files that would change.
+        count: usize,
+    },
+
+    /// `diff` got neither two files nor git's external diff arguments.
+    #[error("diff: expected 2 files (1 with --textconv, or the 1, 7 or 9 arguments of a git external diff), got {count}")]
+    DiffArguments {
+        /// Number of arguments provided.
+        count: usize,
+    },
+
+    /// `diff --breaking` found required keys that were removed.
+    #[error("{} required key(s) removed: {}", keys.len(), keys.join(", "))]
+    BreakingChanges {
+        /// The removed keys.
+        keys: Vec<String>,
+    },
+
+    /// A git command failed.
+    #[error("`{command}` failed: {message}")]
+    GitFailed {
+        /// The command line that failed.
+        command: String,
+        /// Git's error output.
+        message: String,
+    },
+
+    /// The git index has staged changes a fix commit would pick up.
+    #[error("git index has staged changes ({}); commit or unstage them first", files.join(", "))]
+    DirtyIndex {
+        /// Paths with staged changes.
+        files: Vec<String>,
+    },
+
+    /// The command that opens a pull request failed.
+    #[error("PR command `{command}` failed: {message}")]
+    PrFailed {
+        /// The command line that failed.
+        command: String,
+        /// The command's error output.
+        message: String,
+    },
+
     /// Lint errors were found (for exit code purposes).
     #[error("found {error_count} error(s) and {warning_count} warning(s)")]
     LintFailed {
@@ -150,6 +223,24 @@ impl EnvCheckError {
         }
     }
 
+    /// Creates a new schema error.
+    #[must_use]
+    pub fn schema_error(path: impl Into<PathBuf>, message: impl std::fmt::Display) -> Self {
+        Self::SchemaError {
+            path: path.into(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Creates a new invalid config error.
+    #[must_use]
+    pub fn invalid_config(setting: impl Into<String>, message: impl std::fmt::Display) -> Self {
+        Self::InvalidConfig {
+            setting: setting.into(),
+            message: message.to_string(),
+        }
+    }
+
     /// Returns the appropriate exit code for this error.
     #[must_use]
     pub const fn exit_code(&self) -> i32 {
@@ -0,0 +1,87 @@
+//! Thin wrapper around the `git` binary.
+//!
+//! Every command's exit status is checked; failures carry git's stderr.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{EnvCheckError, Result};
+
+/// Runs git commands in a working directory.
+#[derive(Debug, Clone)]
+pub struct Git {
+    dir: PathBuf,
+}
+
+impl Git {
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Runs `git <args>` and returns its stdout.
+    pub fn run<I, S>(&self, args: I) -> Result<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut command = Command::new("git");
+        command.args(args).current_dir(&self.dir);
+        run(&mut command).map_err(|message| EnvCheckError::GitFailed {
+            command: describe(&command),
+            message,
+        })
+    }
+
+    /// Returns the contents of `path` at revision `rev`, or `None` if the file
+    /// did not exist there. Fails if `rev` is not a commit.
+    pub fn show_file(rev: &str, path: &Path) -> Result<Option<String>> {
+        // `rev:./name` resolves relative to the directory git runs in
+        let git = Self::new(match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        });
+        let name = path
+            .file_name()
+            .map_or_else(String::new, |n| n.to_string_lossy().into_owned());
+        let object = format!("{rev}:./{name}");
+
+        git.run(["rev-parse", "--verify", &format!("{rev}^{{commit}}")])?;
+        if git.run(["cat-file", "-e", &object]).is_err() {
+            return Ok(None);
+        }
+        git.run(["show", &object]).map(Some)
+    }
+
+    /// Returns the paths with staged changes.
+    pub fn staged_files(&self) -> Result<Vec<String>> {
+        let output = self.run(["diff", "--cached", "--name-only"])?;
+        Ok(output.lines().map(String::from).collect())
+    }
+}
+
+/// Runs a command and returns its stdout, or a description of why it failed.
+pub(crate) fn run(command: &mut Command) -> std::result::Result<String, String> {
+    let output = command.output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stderr = stderr.trim();
+    Err(if stderr.is_empty() {
+        output.status.to_string()
+    } else {
+        stderr.to_string()
+    })
+}
+
+/// Formats a command line for error messages.
+pub(crate) fn describe(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(OsStr::to_string_lossy)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
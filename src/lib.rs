@@ -19,10 +19,13 @@
 
 pub mod commands;
 pub mod config;
+pub mod diff;
 pub mod error;
+pub mod git;
 pub mod output;
 pub mod parser;
 pub mod rules;
+pub mod schema;
 
 // Re-export main types for convenience
 pub use error::{EnvCheckError, Result};
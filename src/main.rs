@@ -1,8 +1,11 @@
+use std::path::PathBuf;
 use std::process::ExitCode;
 
 use clap::Parser;
 use envcheck::commands::{self, Commands};
+use envcheck::config::Config;
 use envcheck::output::Format;
+use envcheck::parser::Dialect;
 
 #[derive(Parser, Debug)]
 #[command(name = "envcheck")]
@@ -12,9 +15,22 @@ pub struct Cli {
     #[command(subcommand)]
     command: Commands,
 
-    /// Output format (text, json, github)
-    #[arg(long, global = true, default_value = "text")]
-    format: Format,
+    /// Output format (text, json, github, sarif, pr-comment or markdown) [default: config or text]
+    #[arg(long, global = true)]
+    format: Option<Format>,
+
+    /// Dialect to parse .env files with (dotenv, docker, compose, systemd, python-dotenv,
+    /// node-dotenv) [default: config or dotenv]
+    #[arg(long, global = true)]
+    dialect: Option<Dialect>,
+
+    /// Env schema to validate values against [default: config or nearest envcheck.schema.toml]
+    #[arg(long, global = true)]
+    schema: Option<PathBuf>,
+
+    /// Print text output without source snippets
+    #[arg(long, global = true)]
+    compact: bool,
 
     /// Suppress output
     #[arg(short, long, global = true)]
@@ -27,20 +43,94 @@ fn main() -> ExitCode {
     // Initialize logging if needed (e.g. RUST_LOG env var)
     tracing_subscriber::fmt::init();
 
-    let result = match &cli.command {
-        Commands::Lint { files } => commands::lint::run(files, cli.format),
-        Commands::Compare { files } => commands::compare::run(files, cli.format),
-        Commands::Fix { files, commit, pr } => commands::fix::run(files, *commit, *pr),
-        Commands::K8sSync { manifests, env } => commands::k8s_sync::run(manifests, env, cli.format),
-        Commands::Terraform(args) => commands::terraform::run(&args.dir, &args.env, cli.format),
-        Commands::Ansible(args) => commands::ansible::run(&args.dir, &args.env, cli.format),
-        Commands::Actions(args) => commands::actions::run(&args.dir, &args.env, cli.format),
-        Commands::Helm(args) => commands::helm::run(&args.dir, &args.env, cli.format),
-        Commands::Argo(args) => commands::argo::run(&args.dir, &args.env, cli.format),
+    // The nearest .envcheckrc supplies defaults; explicit flags win.
+    let mut config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            if !cli.quiet {
+                eprintln!("Error: {err}");
+            }
+            return ExitCode::from(1);
+        },
+    };
+    if let Some(dialect) = cli.dialect {
+        config.dialect = Some(dialect);
+        config.dialects.clear();
+    }
+    config.compact |= cli.compact;
+    let format = cli
+        .format
+        .or_else(|| config.output_format())
+        .unwrap_or(Format::Text);
+
+    // Only the commands that run lint rules or compare values need the schema,
+    // sort reference and custom rules, so a bad setting doesn't break the others
+    let loaded = match cli.command {
+        Commands::Lint { .. }
+        | Commands::Compare { .. }
+        | Commands::Fix { .. }
+        | Commands::Doctor => config
+            .load_schema(cli.schema.as_deref())
+            .and_then(|()| config.load_sort_reference())
+            .and_then(|()| config.compile_rules()),
+        _ => Ok(()),
+    };
+
+    let result = loaded.and_then(|()| match &cli.command {
+        Commands::Lint { files, git_ref } => {
+            commands::lint::run(files, git_ref.as_deref(), format, &config)
+        },
+        Commands::Compare {
+            files,
+            mode,
+            values,
+            redact,
+            git_ref,
+        } => {
+            let values = commands::compare::ValueOptions {
+                report: *values,
+                redact: redact.unwrap_or(config.compare.redact),
+            };
+            commands::compare::run(files, *mode, values, git_ref.as_deref(), format, &config)
+        },
+        Commands::Diff {
+            files,
+            breaking,
+            textconv,
+            redact,
+        } => {
+            let redaction = redact.unwrap_or(config.compare.redact);
+            commands::diff::run(files, *breaking, *textconv, redaction, format, &config)
+        },
+        Commands::Fix {
+            files,
+            commit,
+            pr,
+            check,
+            diff,
+            stdout,
+        } => {
+            let mode = commands::fix::FixMode {
+                check: *check,
+                diff: *diff,
+                stdout: *stdout,
+            };
+            commands::fix::run(files, *commit, *pr, mode, &config)
+        },
+        Commands::K8sSync { manifests, env } => {
+            commands::k8s_sync::run(manifests, env, format, &config)
+        },
+        Commands::Terraform(args) => {
+            commands::terraform::run(&args.dir, &args.env, format, &config)
+        },
+        Commands::Ansible(args) => commands::ansible::run(&args.dir, &args.env, format, &config),
+        Commands::Actions(args) => commands::actions::run(&args.dir, &args.env, format, &config),
+        Commands::Helm(args) => commands::helm::run(&args.dir, &args.env, format, &config),
+        Commands::Argo(args) => commands::argo::run(&args.dir, &args.env, format, &config),
         Commands::Completions { shell } => commands::completions::run(*shell),
         Commands::Tui { files } => commands::tui::run(files),
-        Commands::Doctor => commands::doctor::run(cli.format),
-    };
+        Commands::Doctor => commands::doctor::run(format, &config),
+    });
 
     match result {
         Ok(_) => ExitCode::SUCCESS,
@@ -0,0 +1,164 @@
+use std::io::{self, Write};
+
+use crate::diff::{EnvDiff, KeyChanges};
+use crate::output::Format;
+
+/// Writes key changes as JSON, markdown (`pr-comment`), or plain text.
+/// See [`Format::require_document`] for the other formats.
+pub fn write_changes(
+    format: Format,
+    changes: &[KeyChanges],
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    match format {
+        Format::Json => writeln!(writer, "{}", serde_json::to_string_pretty(changes)?),
+        Format::PrComment => changes.iter().try_for_each(|c| write_markdown(c, writer)),
+        Format::Text => changes.iter().try_for_each(|c| write_text(c, writer)),
+        Format::Github | Format::Sarif => Err(io::ErrorKind::Unsupported.into()),
+    }
+}
+
+fn write_text(changes: &KeyChanges, writer: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        writer,
+        "{} ({} → {})",
+        changes.path.display(),
+        changes.from,
+        changes.to
+    )?;
+    if changes.is_empty() {
+        return writeln!(writer, "  no key changes");
+    }
+    write_key_lines(changes, writer)
+}
+
+fn write_key_lines(changes: &KeyChanges, writer: &mut dyn Write) -> io::Result<()> {
+    for key in &changes.added {
+        writeln!(writer, "  + {key}")?;
+    }
+    for key in &changes.removed {
+        writeln!(writer, "  - {key}")?;
+    }
+    for rename in &changes.renamed {
+        writeln!(writer, "  ~ {} → {}", rename.from, rename.to)?;
+    }
+    Ok(())
+}
+
+fn write_markdown(changes: &KeyChanges, writer: &mut dyn Write) -> io::Result<()> {
+    writeln!(
+        writer,
+        "### `{}` ({} → {})\n",
+        changes.path.display(),
+        changes.from,
+        changes.to
+    )?;
+    if changes.is_empty() {
+        return writeln!(writer, "No key changes.\n");
+    }
+    writeln!(writer, "| Change | Key |\n|--------|-----|")?;
+    write_key_rows(changes, writer)?;
+    writeln!(writer)
+}
+
+fn write_key_rows(changes: &KeyChanges, writer: &mut dyn Write) -> io::Result<()> {
+    for key in &changes.added {
+        writeln!(writer, "| ➕ Added | `{key}` |")?;
+    }
+    for key in &changes.removed {
+        writeln!(writer, "| ➖ Removed | `{key}` |")?;
+    }
+    for rename in &changes.renamed {
+        writeln!(
+            writer,
+            "| ✏️ Renamed | `{}` → `{}` |",
+            rename.from, rename.to
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes a semantic diff. With `breaking_only`, text and markdown list only
+/// the removed required keys; JSON always carries the whole diff.
+/// See [`Format::require_document`] for the other formats.
+pub fn write_diff(
+    format: Format,
+    diff: &EnvDiff,
+    breaking_only: bool,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    match format {
+        Format::Json => writeln!(writer, "{}", serde_json::to_string_pretty(diff)?),
+        Format::PrComment => write_diff_markdown(diff, breaking_only, writer),
+        Format::Text => write_diff_text(diff, breaking_only, writer),
+        Format::Github | Format::Sarif => Err(io::ErrorKind::Unsupported.into()),
+    }
+}
+
+fn write_diff_text(diff: &EnvDiff, breaking_only: bool, writer: &mut dyn Write) -> io::Result<()> {
+    writeln!(writer, "--- {}\n+++ {}", diff.keys.from, diff.keys.to)?;
+    if breaking_only {
+        if diff.breaking.is_empty() {
+            return writeln!(writer, "  no breaking changes");
+        }
+        for key in &diff.breaking {
+            writeln!(writer, "  ! {key} (required) removed")?;
+        }
+        return Ok(());
+    }
+    if diff.is_empty() {
+        return writeln!(writer, "  no semantic changes");
+    }
+    write_key_lines(&diff.keys, writer)?;
+    for change in &diff.changed {
+        writeln!(
+            writer,
+            "  * {}: {} → {}",
+            change.key, change.from, change.to
+        )?;
+    }
+    if diff.reordered {
+        writeln!(writer, "  keys reordered")?;
+    }
+    if diff.comments_changed {
+        writeln!(writer, "  comments changed")?;
+    }
+    Ok(())
+}
+
+fn write_diff_markdown(
+    diff: &EnvDiff,
+    breaking_only: bool,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    writeln!(writer, "### `{}` → `{}`\n", diff.keys.from, diff.keys.to)?;
+    if breaking_only {
+        if diff.breaking.is_empty() {
+            return writeln!(writer, "No breaking changes.\n");
+        }
+        writeln!(writer, "Required keys removed:\n")?;
+        for key in &diff.breaking {
+            writeln!(writer, "- `{key}`")?;
+        }
+        return writeln!(writer);
+    }
+    if diff.is_empty() {
+        return writeln!(writer, "No semantic changes.\n");
+    }
+    writeln!(writer, "| Change | Key |\n|--------|-----|")?;
+    write_key_rows(&diff.keys, writer)?;
+    for change in &diff.changed {
+        writeln!(
+            writer,
+            "| 🔁 Changed | `{}`: {} → {} |",
+            change.key, change.from, change.to
+        )?;
+    }
+    if diff.reordered {
+        writeln!(writer, "| ↕️ Reordered | |")?;
+    }
+    if diff.comments_changed {
+        writeln!(writer, "| 💬 Comments | |")?;
+    }
+    writeln!(writer)
+}
@@ -14,14 +14,20 @@ impl OutputFormatter for GithubFormatter {
                 Severity::Info => "notice",
             };
 
-            // GitHub Actions format: ::command file={name},line={line}::{message}
+            // GitHub Actions format: ::command file={name},line={line},col={col}::{message}
             // See: https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions#setting-an-error-message
 
             let path = diagnostic.path.to_string_lossy();
-            let line_part = if let Some(line) = diagnostic.line {
-                format!("line={line},")
-            } else {
-                String::new()
+            let line_part = match (diagnostic.line, diagnostic.span) {
+                // GitHub's endColumn is inclusive
+                (Some(line), Some(span)) => format!(
+                    "line={line},endLine={},col={},endColumn={},",
+                    span.end_line,
+                    span.column,
+                    span.end_column.saturating_sub(1).max(span.column)
+                ),
+                (Some(line), None) => format!("line={line},"),
+                (None, _) => String::new(),
             };
 
             // Escape message data
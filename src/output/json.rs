@@ -2,7 +2,8 @@ use serde::Serialize;
 use std::io::Write;
 
 use crate::output::OutputFormatter;
-use crate::rules::Diagnostic;
+use crate::rules::{Diagnostic, Fix};
+use crate::schema::Violation;
 
 #[derive(Serialize)]
 struct JsonDiagnostic {
@@ -11,6 +12,52 @@ struct JsonDiagnostic {
     message: String,
     file: String,
     line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    constraint: Option<Violation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<JsonFix>,
+}
+
+#[derive(Serialize)]
+struct JsonFix {
+    message: String,
+    edits: Vec<JsonEdit>,
+}
+
+#[derive(Serialize)]
+struct JsonEdit {
+    line: usize,
+    column: usize,
+    end_line: usize,
+    end_column: usize,
+    /// Byte offsets of the replaced range.
+    start: usize,
+    end: usize,
+    replacement: String,
+}
+
+impl From<&Fix> for JsonFix {
+    fn from(fix: &Fix) -> Self {
+        Self {
+            message: fix.message.clone(),
+            edits: fix
+                .edits
+                .iter()
+                .map(|edit| JsonEdit {
+                    line: edit.line,
+                    column: edit.span.column,
+                    end_line: edit.span.end_line,
+                    end_column: edit.span.end_column,
+                    start: edit.span.start,
+                    end: edit.span.end,
+                    replacement: edit.replacement.clone(),
+                })
+                .collect(),
+        }
+    }
 }
 
 pub struct JsonFormatter;
@@ -25,6 +72,11 @@ impl OutputFormatter for JsonFormatter {
                 message: d.message.clone(),
                 file: d.path.to_string_lossy().to_string(),
                 line: d.line,
+                column: d.span.map(|s| s.column),
+                end_line: d.span.map(|s| s.end_line),
+                end_column: d.span.map(|s| s.end_column),
+                constraint: d.constraint.clone(),
+                fix: d.fix.as_ref().map(JsonFix::from),
             })
             .collect();
 
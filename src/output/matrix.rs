@@ -0,0 +1,101 @@
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::output::Format;
+use crate::parser::EnvFile;
+
+/// Which files define each key, as printed by `compare --mode matrix`.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyMatrix {
+    pub files: Vec<String>,
+    pub keys: Vec<KeyPresence>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyPresence {
+    pub key: String,
+    /// One entry per file, in the order of [`KeyMatrix::files`].
+    pub present: Vec<bool>,
+}
+
+impl KeyMatrix {
+    /// Builds the matrix. Keys are listed in order of first appearance.
+    #[must_use]
+    pub fn new(env_files: &[EnvFile]) -> Self {
+        let mut keys: Vec<KeyPresence> = Vec::new();
+        for (i, env_file) in env_files.iter().enumerate() {
+            for var in &env_file.vars {
+                let row = keys.iter().position(|row| row.key == var.key);
+                let row = row.unwrap_or_else(|| {
+                    keys.push(KeyPresence {
+                        key: var.key.clone(),
+                        present: vec![false; env_files.len()],
+                    });
+                    keys.len() - 1
+                });
+                keys[row].present[i] = true;
+            }
+        }
+
+        Self {
+            files: env_files
+                .iter()
+                .map(|f| f.path.display().to_string())
+                .collect(),
+            keys,
+        }
+    }
+
+    /// Writes the matrix as JSON, a markdown table (`pr-comment`), or plain text.
+    /// See [`Format::require_document`] for the other formats.
+    pub fn write(&self, format: Format, writer: &mut dyn Write) -> io::Result<()> {
+        match format {
+            Format::Json => writeln!(writer, "{}", serde_json::to_string_pretty(self)?),
+            Format::PrComment => self.write_markdown(writer),
+            Format::Text => self.write_text(writer),
+            Format::Github | Format::Sarif => Err(io::ErrorKind::Unsupported.into()),
+        }
+    }
+
+    fn write_text(&self, writer: &mut dyn Write) -> io::Result<()> {
+        let key_width = self
+            .keys
+            .iter()
+            .map(|row| row.key.chars().count())
+            .chain(std::iter::once("KEY".len()))
+            .max()
+            .unwrap_or_default();
+        let widths: Vec<usize> = self.files.iter().map(|f| f.chars().count()).collect();
+
+        let mut line = format!("{:key_width$}", "KEY");
+        for file in &self.files {
+            line.push_str(&format!("  {file}"));
+        }
+        writeln!(writer, "{line}")?;
+
+        for row in &self.keys {
+            let mut line = format!("{:key_width$}", row.key);
+            for (present, width) in row.present.iter().zip(&widths) {
+                let mark = if *present { "✓" } else { "✗" };
+                line.push_str(&format!("  {mark:width$}"));
+            }
+            writeln!(writer, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+
+    fn write_markdown(&self, writer: &mut dyn Write) -> io::Result<()> {
+        writeln!(writer, "| Key | {} |", self.files.join(" | "))?;
+        writeln!(writer, "|-----|{}", "---|".repeat(self.files.len()))?;
+        for row in &self.keys {
+            let marks: Vec<&str> = row
+                .present
+                .iter()
+                .map(|&present| if present { "✅" } else { "❌" })
+                .collect();
+            writeln!(writer, "| `{}` | {} |", row.key, marks.join(" | "))?;
+        }
+        Ok(())
+    }
+}
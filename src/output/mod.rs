@@ -1,15 +1,22 @@
+use crate::parser::EnvFile;
 use crate::rules::Diagnostic;
+use std::collections::HashMap;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 
+mod changes;
 mod github;
 mod json;
+mod matrix;
 mod pr_comment;
 mod sarif;
 mod text;
 
+pub use changes::{write_changes, write_diff};
 pub use github::GithubFormatter;
 pub use json::JsonFormatter;
-pub use text::TextFormatter;
+pub use matrix::{KeyMatrix, KeyPresence};
+pub use text::{RichTextFormatter, TextFormatter};
 
 /// Supported output formats.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -30,18 +37,76 @@ impl std::str::FromStr for Format {
             "json" => Ok(Self::Json),
             "github" => Ok(Self::Github),
             "sarif" => Ok(Self::Sarif),
-            "pr-comment" | "pr_comment" => Ok(Self::PrComment),
+            "pr-comment" | "pr_comment" | "markdown" => Ok(Self::PrComment),
             _ => Err(format!("Unknown format: {s}")),
         }
     }
 }
 
+/// Source lines of the files diagnostics point into, for formatters that show snippets.
+#[derive(Debug, Clone, Default)]
+pub struct Sources {
+    files: HashMap<PathBuf, Vec<String>>,
+}
+
+impl Sources {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the lines of a parsed file.
+    pub fn insert(&mut self, env_file: &EnvFile) {
+        self.files
+            .insert(env_file.path.clone(), env_file.lines.clone());
+    }
+
+    /// Returns a 1-indexed line of a file.
+    #[must_use]
+    pub fn line(&self, path: &Path, line: usize) -> Option<&str> {
+        self.files
+            .get(path)?
+            .get(line.checked_sub(1)?)
+            .map(String::as_str)
+    }
+}
+
+impl Format {
+    /// Fails for `github` and `sarif`, which only describe diagnostics, when
+    /// a command prints `output` (a table or a diff) instead.
+    pub fn require_document(self, output: &str) -> crate::error::Result<()> {
+        let format = match self {
+            Self::Github => "github",
+            Self::Sarif => "sarif",
+            Self::Text | Self::Json | Self::PrComment => return Ok(()),
+        };
+        Err(crate::error::EnvCheckError::UnsupportedFormat {
+            output: output.to_string(),
+            format: format.to_string(),
+        })
+    }
+}
+
 /// Trait for diagnostic formatters.
 pub trait OutputFormatter {
     /// Writes the diagnostics to the writer.
     fn write(&self, diagnostics: &[Diagnostic], writer: &mut dyn Write) -> std::io::Result<()>;
 }
 
+/// Writes diagnostics using the specified format. Text output shows source
+/// snippets when `sources` is given, and is compact otherwise.
+pub fn write_report(
+    format: Format,
+    diagnostics: &[Diagnostic],
+    sources: Option<&Sources>,
+    writer: &mut dyn Write,
+) -> std::io::Result<()> {
+    match (format, sources) {
+        (Format::Text, Some(sources)) => RichTextFormatter { sources }.write(diagnostics, writer),
+        _ => write_diagnostics(format, diagnostics, writer),
+    }
+}
+
 /// Writes diagnostics using the specified format.
 pub fn write_diagnostics(
     format: Format,
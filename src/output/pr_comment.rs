@@ -87,6 +87,10 @@ mod tests {
             message: "Duplicate key".to_string(),
             path: PathBuf::from(".env"),
             line: Some(5),
+            span: None,
+            labels: Vec::new(),
+            constraint: None,
+            fix: None,
         }];
         let comment = generate_pr_comment(&diagnostics);
         assert!(comment.contains("❌"));
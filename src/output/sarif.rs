@@ -64,6 +64,29 @@ pub struct SarifResult {
     pub level: String,
     pub message: SarifMessage,
     pub locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixes: Vec<SarifFix>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifFix {
+    pub description: SarifMessage,
+    pub artifact_changes: Vec<SarifArtifactChange>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifArtifactChange {
+    pub artifact_location: SarifArtifactLocation,
+    pub replacements: Vec<SarifReplacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifReplacement {
+    pub deleted_region: SarifRegion,
+    pub inserted_content: SarifMessage,
 }
 
 #[derive(Serialize)]
@@ -89,6 +112,16 @@ pub struct SarifArtifactLocation {
 #[serde(rename_all = "camelCase")]
 pub struct SarifRegion {
     pub start_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_offset: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_length: Option<usize>,
 }
 
 pub fn write_sarif(diagnostics: &[Diagnostic], writer: &mut dyn Write) -> io::Result<()> {
@@ -111,9 +144,45 @@ pub fn write_sarif(diagnostics: &[Diagnostic], writer: &mut dyn Write) -> io::Re
                     },
                     region: SarifRegion {
                         start_line: d.line.unwrap_or(1),
+                        start_column: d.span.map(|s| s.column),
+                        end_line: d.span.map(|s| s.end_line),
+                        end_column: d.span.map(|s| s.end_column),
+                        byte_offset: d.span.map(|s| s.start),
+                        byte_length: d.span.map(|s| s.end - s.start),
                     },
                 },
             }],
+            fixes: d
+                .fix
+                .iter()
+                .map(|fix| SarifFix {
+                    description: SarifMessage {
+                        text: fix.message.clone(),
+                    },
+                    artifact_changes: vec![SarifArtifactChange {
+                        artifact_location: SarifArtifactLocation {
+                            uri: d.path.display().to_string(),
+                        },
+                        replacements: fix
+                            .edits
+                            .iter()
+                            .map(|edit| SarifReplacement {
+                                deleted_region: SarifRegion {
+                                    start_line: edit.line,
+                                    start_column: Some(edit.span.column),
+                                    end_line: Some(edit.span.end_line),
+                                    end_column: Some(edit.span.end_column),
+                                    byte_offset: Some(edit.span.start),
+                                    byte_length: Some(edit.span.end - edit.span.start),
+                                },
+                                inserted_content: SarifMessage {
+                                    text: edit.replacement.clone(),
+                                },
+                            })
+                            .collect(),
+                    }],
+                })
+                .collect(),
         })
         .collect();
 
@@ -1,9 +1,10 @@
 use colored::*;
 use std::io::Write;
 
-use crate::output::OutputFormatter;
-use crate::rules::{Diagnostic, Severity};
+use crate::output::{OutputFormatter, Sources};
+use crate::rules::{Diagnostic, Severity, Span};
 
+/// Compact text output: one message and location per diagnostic.
 pub struct TextFormatter;
 
 impl OutputFormatter for TextFormatter {
@@ -17,20 +18,25 @@ impl OutputFormatter for TextFormatter {
         // Let's print sequentially for now, simpler.
 
         for diagnostic in diagnostics {
-            let severity_str = match diagnostic.severity {
-                Severity::Error => "error".red().bold(),
-                Severity::Warning => "warning".yellow().bold(),
-                Severity::Info => "info".blue().bold(),
-            };
+            let severity_str = severity_label(diagnostic.severity);
 
             let code = diagnostic.id.to_string().white().bold();
 
             // Format: error[E001]: message
             writeln!(writer, "{}[{}]: {}", severity_str, code, diagnostic.message)?;
 
-            // Location:  --> file:line
+            // Location:  --> file:line:column
             let path_str = diagnostic.path.to_string_lossy();
-            if let Some(line) = diagnostic.line {
+            if let (Some(line), Some(span)) = (diagnostic.line, diagnostic.span) {
+                writeln!(
+                    writer,
+                    "  {} {}:{}:{}",
+                    "-->".blue(),
+                    path_str,
+                    line,
+                    span.column
+                )?;
+            } else if let Some(line) = diagnostic.line {
                 writeln!(writer, "  {} {}:{}", "-->".blue(), path_str, line)?;
             } else {
                 writeln!(writer, "  {} {}", "-->".blue(), path_str)?;
@@ -42,3 +48,147 @@ impl OutputFormatter for TextFormatter {
         Ok(())
     }
 }
+
+/// Text output with rustc-style source snippets.
+///
+/// Diagnostics without a span, or whose file is not in `sources`, are printed in
+/// the compact [`TextFormatter`] form.
+pub struct RichTextFormatter<'a> {
+    pub sources: &'a Sources,
+}
+
+/// A line of a snippet with the marks to draw under it.
+struct Annotation<'a> {
+    line: usize,
+    span: Span,
+    message: &'a str,
+    primary: bool,
+}
+
+impl OutputFormatter for RichTextFormatter<'_> {
+    fn write(&self, diagnostics: &[Diagnostic], writer: &mut dyn Write) -> std::io::Result<()> {
+        for diagnostic in diagnostics {
+            let (Some(line), Some(span)) = (diagnostic.line, diagnostic.span) else {
+                TextFormatter.write(std::slice::from_ref(diagnostic), writer)?;
+                continue;
+            };
+            if self.sources.line(&diagnostic.path, line).is_none() {
+                TextFormatter.write(std::slice::from_ref(diagnostic), writer)?;
+                continue;
+            }
+
+            let mut annotations = vec![Annotation {
+                line,
+                span,
+                message: "",
+                primary: true,
+            }];
+            annotations.extend(diagnostic.labels.iter().map(|label| Annotation {
+                line: label.line,
+                span: label.span,
+                message: &label.message,
+                primary: false,
+            }));
+            annotations.sort_by_key(|a| (a.line, !a.primary));
+
+            let width = annotations
+                .iter()
+                .map(|a| a.line.to_string().len())
+                .max()
+                .unwrap_or(1);
+            let gutter = format!("{:width$} |", "").blue().bold();
+
+            writeln!(
+                writer,
+                "{}[{}]: {}",
+                severity_label(diagnostic.severity),
+                diagnostic.id.to_string().white().bold(),
+                diagnostic.message
+            )?;
+            writeln!(
+                writer,
+                "{:width$}{} {}:{}:{}",
+                "",
+                "-->".blue().bold(),
+                diagnostic.path.to_string_lossy(),
+                line,
+                span.column
+            )?;
+            writeln!(writer, "{gutter}")?;
+
+            let mut previous: Option<usize> = None;
+            for annotation in &annotations {
+                let Some(text) = self.sources.line(&diagnostic.path, annotation.line) else {
+                    continue;
+                };
+
+                if previous != Some(annotation.line) {
+                    if previous.is_some_and(|p| annotation.line > p + 1) {
+                        writeln!(writer, "{}", "...".blue().bold())?;
+                    }
+                    let number = format!("{:>width$} |", annotation.line).blue().bold();
+                    writeln!(writer, "{number} {}", expand_tabs(text))?;
+                }
+                previous = Some(annotation.line);
+
+                let (offset, length) = underline(text, annotation);
+                let marks = if annotation.primary {
+                    let marks = "^".repeat(length);
+                    match diagnostic.severity {
+                        Severity::Error => marks.red().bold(),
+                        Severity::Warning => marks.yellow().bold(),
+                        Severity::Info => marks.blue().bold(),
+                    }
+                } else {
+                    "-".repeat(length).blue().bold()
+                };
+                let message = if annotation.message.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}", annotation.message.blue().bold())
+                };
+                writeln!(writer, "{gutter} {:offset$}{marks}{message}", "")?;
+            }
+
+            writeln!(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn severity_label(severity: Severity) -> ColoredString {
+    match severity {
+        Severity::Error => "error".red().bold(),
+        Severity::Warning => "warning".yellow().bold(),
+        Severity::Info => "info".blue().bold(),
+    }
+}
+
+const TAB_WIDTH: usize = 4;
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+/// Returns the display offset and length of the marks under `text`. Spans that
+/// continue on later lines are underlined to the end of the line.
+fn underline(text: &str, annotation: &Annotation<'_>) -> (usize, usize) {
+    let chars: Vec<char> = text.chars().collect();
+    let start = (annotation.span.column - 1).min(chars.len());
+    let end = if annotation.span.end_line == annotation.line {
+        (annotation.span.end_column - 1).clamp(start, chars.len())
+    } else {
+        chars.len()
+    };
+
+    let before: String = chars[..start].iter().collect();
+    let marked: String = chars[start..end].iter().collect();
+    (display_width(&before), display_width(&marked).max(1))
+}
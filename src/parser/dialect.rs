@@ -0,0 +1,200 @@
+//! Dotenv dialects: the different ways tools read the same `.env` file.
+
+use std::fmt;
+
+use serde::Deserialize;
+
+/// A consumer of `.env` files, each with its own parsing rules.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dialect {
+    /// The common dotenv semantics envcheck uses by default.
+    #[default]
+    Dotenv,
+    /// `docker run --env-file`: every line is taken literally.
+    Docker,
+    /// Docker Compose `env_file` / `.env`.
+    Compose,
+    /// systemd `EnvironmentFile=`.
+    Systemd,
+    /// The `python-dotenv` package.
+    PythonDotenv,
+    /// The `dotenv` npm package (without `dotenv-expand`).
+    NodeDotenv,
+}
+
+/// How an unquoted value ends at a `#`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlineComments {
+    /// `#` never starts a comment after the value.
+    None,
+    /// `#` starts a comment when preceded by whitespace.
+    AfterWhitespace,
+    /// Any `#` starts a comment.
+    Anywhere,
+}
+
+/// Which escape sequences are processed inside double quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escapes {
+    /// Backslashes are literal.
+    None,
+    /// Only `\n` becomes a newline.
+    NewlineOnly,
+    /// `\n`, `\r`, `\t`, `\"`, `\\` and `\$`.
+    Full,
+}
+
+/// Which `$` references are expanded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Values are never expanded.
+    None,
+    /// Only `${VAR}` forms are expanded.
+    BracesOnly,
+    /// Both `$VAR` and `${VAR}` forms are expanded.
+    Full,
+}
+
+/// The parsing behaviour of a [`Dialect`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct DialectFeatures {
+    /// Surrounding single and double quotes are removed.
+    pub quotes: bool,
+    /// Backticks quote values like single quotes.
+    pub backticks: bool,
+    /// Quoted values may span several lines.
+    pub multiline: bool,
+    /// A trailing backslash joins an unquoted value with the next line.
+    pub line_continuation: bool,
+    /// An `export ` prefix is accepted.
+    pub export: bool,
+    /// Inline comment handling for unquoted values.
+    pub inline_comments: InlineComments,
+    /// Escape processing in double-quoted values.
+    pub escapes: Escapes,
+    /// Variable expansion.
+    pub interpolation: Interpolation,
+}
+
+impl Dialect {
+    /// All dialects, in the order they are documented.
+    pub const ALL: [Self; 6] = [
+        Self::Dotenv,
+        Self::Docker,
+        Self::Compose,
+        Self::Systemd,
+        Self::PythonDotenv,
+        Self::NodeDotenv,
+    ];
+
+    /// Returns the parsing behaviour of this dialect.
+    #[must_use]
+    pub const fn features(self) -> DialectFeatures {
+        match self {
+            Self::Dotenv => DialectFeatures {
+                quotes: true,
+                backticks: true,
+                multiline: true,
+                line_continuation: false,
+                export: true,
+                inline_comments: InlineComments::AfterWhitespace,
+                escapes: Escapes::Full,
+                interpolation: Interpolation::Full,
+            },
+            Self::Docker => DialectFeatures {
+                quotes: false,
+                backticks: false,
+                multiline: false,
+                line_continuation: false,
+                export: false,
+                inline_comments: InlineComments::None,
+                escapes: Escapes::None,
+                interpolation: Interpolation::None,
+            },
+            Self::Compose => DialectFeatures {
+                quotes: true,
+                backticks: false,
+                multiline: true,
+                line_continuation: false,
+                export: true,
+                inline_comments: InlineComments::AfterWhitespace,
+                escapes: Escapes::Full,
+                interpolation: Interpolation::Full,
+            },
+            Self::Systemd => DialectFeatures {
+                quotes: true,
+                backticks: false,
+                multiline: false,
+                line_continuation: true,
+                export: false,
+                inline_comments: InlineComments::None,
+                escapes: Escapes::Full,
+                interpolation: Interpolation::None,
+            },
+            Self::PythonDotenv => DialectFeatures {
+                quotes: true,
+                backticks: false,
+                multiline: true,
+                line_continuation: false,
+                export: true,
+                inline_comments: InlineComments::AfterWhitespace,
+                escapes: Escapes::Full,
+                interpolation: Interpolation::BracesOnly,
+            },
+            Self::NodeDotenv => DialectFeatures {
+                quotes: true,
+                backticks: true,
+                multiline: true,
+                line_continuation: false,
+                export: true,
+                inline_comments: InlineComments::Anywhere,
+                escapes: Escapes::NewlineOnly,
+                interpolation: Interpolation::None,
+            },
+        }
+    }
+
+    /// Whether files of this dialect are also sourced by shells. Docker and
+    /// systemd read them directly, so shell hazards don't apply.
+    #[must_use]
+    pub const fn shell_sourced(self) -> bool {
+        !matches!(self, Self::Docker | Self::Systemd)
+    }
+
+    /// Returns the name used on the command line and in config files.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Dotenv => "dotenv",
+            Self::Docker => "docker",
+            Self::Compose => "compose",
+            Self::Systemd => "systemd",
+            Self::PythonDotenv => "python-dotenv",
+            Self::NodeDotenv => "node-dotenv",
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dotenv" => Ok(Self::Dotenv),
+            "docker" => Ok(Self::Docker),
+            "compose" | "docker-compose" => Ok(Self::Compose),
+            "systemd" => Ok(Self::Systemd),
+            "python-dotenv" | "python" => Ok(Self::PythonDotenv),
+            "node-dotenv" | "node" => Ok(Self::NodeDotenv),
+            _ => Err(format!("Unknown dialect: {s}")),
+        }
+    }
+}
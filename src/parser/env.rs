@@ -1,8 +1,49 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
 use std::fs;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 use crate::error::{EnvCheckError, Result};
+use crate::parser::dialect::{Dialect, DialectFeatures, Escapes, InlineComments};
+
+/// How a value was quoted in the source file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum QuoteStyle {
+    /// Bare value; inline `#` comments are stripped.
+    #[default]
+    None,
+    /// `'value'` - taken literally.
+    Single,
+    /// `"value"` - escapes are processed and the value may span lines.
+    Double,
+    /// `` `value` `` - taken literally.
+    Backtick,
+}
+
+impl QuoteStyle {
+    const fn from_char(c: char) -> Option<Self> {
+        match c {
+            '\'' => Some(Self::Single),
+            '"' => Some(Self::Double),
+            '`' => Some(Self::Backtick),
+            _ => None,
+        }
+    }
+
+    /// Returns the quote character, if any.
+    #[must_use]
+    pub const fn as_char(self) -> Option<char> {
+        match self {
+            Self::None => None,
+            Self::Single => Some('\''),
+            Self::Double => Some('"'),
+            Self::Backtick => Some('`'),
+        }
+    }
+}
 
 /// Represents a single environment variable entry in a .env file.
 /// Uses Cow<str> for zero-copy parsing when possible.
@@ -10,14 +51,32 @@ use crate::error::{EnvCheckError, Result};
 pub struct EnvVar {
     /// The variable key (e.g., "DATABASE_URL").
     pub key: String,
-    /// The variable value.
+    /// The variable value, with quotes removed and escapes processed.
     pub value: String,
     /// The line number in the file (1-indexed).
     pub line: usize,
+    /// The line the value ends on; differs from `line` for multi-line values.
+    pub end_line: usize,
     /// True if the variable is exported (starts with `export`).
     pub exported: bool,
-    /// True if the key is quoted.
+    /// True if the value is quoted.
     pub quoted: bool,
+    /// The quoting used for the value.
+    pub quote: QuoteStyle,
+    /// The value exactly as written, including quotes but not any inline comment.
+    pub raw_value: String,
+    /// Byte range of the key in the file.
+    pub key_span: Range<usize>,
+    /// Byte range of `raw_value` in the file.
+    pub raw_span: Range<usize>,
+}
+
+impl EnvVar {
+    /// Returns true if the value spans more than one line.
+    #[must_use]
+    pub const fn is_multiline(&self) -> bool {
+        self.end_line > self.line
+    }
 }
 
 /// Zero-copy version of EnvVar for internal parsing
@@ -25,12 +84,22 @@ pub struct EnvVar {
 pub struct EnvVarRef<'a> {
     /// The variable key (borrowed when possible).
     pub key: Cow<'a, str>,
-    /// The variable value (borrowed when possible).
+    /// The variable value (borrowed unless escapes had to be processed).
     pub value: Cow<'a, str>,
     /// The line number in the file (1-indexed).
     pub line: usize,
+    /// The line the value ends on (1-indexed).
+    pub end_line: usize,
     /// True if the variable is exported.
     pub exported: bool,
+    /// The quoting used for the value.
+    pub quote: QuoteStyle,
+    /// The value exactly as written, including quotes but not any inline comment.
+    pub raw_value: &'a str,
+    /// Byte range of the key in the parsed content.
+    pub key_span: Range<usize>,
+    /// Byte range of `raw_value` in the parsed content.
+    pub raw_span: Range<usize>,
 }
 
 impl<'a> EnvVarRef<'a> {
@@ -41,8 +110,13 @@ impl<'a> EnvVarRef<'a> {
             key: self.key.to_string(),
             value: self.value.to_string(),
             line: self.line,
+            end_line: self.end_line,
             exported: self.exported,
-            quoted: false,
+            quoted: self.quote != QuoteStyle::None,
+            quote: self.quote,
+            raw_value: self.raw_value.to_string(),
+            key_span: self.key_span.clone(),
+            raw_span: self.raw_span.clone(),
         }
     }
 }
@@ -56,92 +130,474 @@ pub struct EnvFile {
     pub vars: Vec<EnvVar>,
     /// Raw lines of the file (for reporting context).
     pub lines: Vec<String>,
+    /// The dialect the file was parsed with.
+    pub dialect: Dialect,
+    /// Lossless syntax tree of the file.
+    pub syntax: SyntaxTree,
+    /// Values with references expanded, resolved on first use.
+    resolved: OnceLock<HashMap<String, String>>,
 }
 
 impl EnvFile {
     /// Parses a .env file from the given path.
     pub fn parse(path: impl Into<PathBuf>) -> Result<Self> {
+        Self::parse_with_dialect(path, Dialect::default())
+    }
+
+    /// Parses a .env file the way `dialect` reads it.
+    pub fn parse_with_dialect(path: impl Into<PathBuf>, dialect: Dialect) -> Result<Self> {
         let path = path.into();
         let content = fs::read_to_string(&path).map_err(|e| EnvCheckError::read_error(&path, e))?;
-        Self::parse_content(path, &content)
+        Self::parse_content_with_dialect(path, &content, dialect)
     }
 
     /// Parses .env content from a string.
     pub fn parse_content(path: PathBuf, content: &str) -> Result<Self> {
+        Self::parse_content_with_dialect(path, content, Dialect::default())
+    }
+
+    /// Parses .env content from a string the way `dialect` reads it.
+    pub fn parse_content_with_dialect(
+        path: PathBuf,
+        content: &str,
+        dialect: Dialect,
+    ) -> Result<Self> {
         let lines: Vec<String> = content.lines().map(String::from).collect();
-        let vars = parse_env_zero_copy(content);
+        let (syntax, vars) = parse_syntax(content, dialect);
+
+        Ok(Self {
+            path,
+            vars,
+            lines,
+            dialect,
+            syntax,
+            resolved: OnceLock::new(),
+        })
+    }
 
-        Ok(Self { path, vars, lines })
+    /// Returns the value of `key` with `${VAR}` references expanded from the keys
+    /// defined above it. The file is resolved once, on the first call. See
+    /// [`Interpolator`](crate::parser::interpolate::Interpolator) to also resolve
+    /// from the process environment.
+    #[must_use]
+    pub fn resolved_value(&self, key: &str) -> Option<&str> {
+        self.resolved
+            .get_or_init(|| crate::parser::interpolate::Interpolator::new().resolve(self))
+            .get(key)
+            .map(String::as_str)
+    }
+
+    /// Returns the variable whose multi-line value covers the given line, if any.
+    #[must_use]
+    pub fn multiline_var_at(&self, line: usize) -> Option<&EnvVar> {
+        self.vars
+            .iter()
+            .find(|v| v.is_multiline() && v.line <= line && line <= v.end_line)
     }
 }
 
-/// Zero-copy parser for .env content
-/// Returns borrowed references where possible, owned strings only when trimming is needed
-fn parse_env_zero_copy(content: &str) -> Vec<EnvVar> {
-    let mut vars = Vec::new();
+/// Kind of a [`Token`] in a [`SyntaxTree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// Spaces and tabs.
+    Whitespace,
+    /// A full-line `# comment`.
+    Comment,
+    /// The `export` keyword.
+    Export,
+    /// A variable name.
+    Key,
+    /// The `=` between key and value.
+    Operator,
+    /// An opening or closing quote.
+    Quote,
+    /// The value as written, without its quotes.
+    Value,
+    /// A `# comment` after a value.
+    InlineComment,
+    /// `\n` or `\r\n`.
+    LineEnding,
+    /// Text the parser ignores: a line that is not an assignment, or text after a
+    /// closing quote.
+    Invalid,
+}
 
-    for (i, line) in content.lines().enumerate() {
-        let line_num = i + 1;
-        let trimmed = line.trim();
+/// A token and its byte range in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
 
-        // Skip empty lines and comments
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
-        }
+/// Kind of a [`SyntaxNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// An empty or whitespace-only line.
+    Blank,
+    /// A comment line.
+    Comment,
+    /// A `KEY=value` assignment.
+    Entry,
+    /// A line that is not an assignment.
+    Invalid,
+}
 
-        // Handle optional 'export' prefix
-        let (is_exported, content) = if let Some(stripped) = trimmed.strip_prefix("export ") {
-            (true, stripped.trim())
-        } else {
-            (false, trimmed)
-        };
+/// A logical line: one physical line, or several for multi-line values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxNode {
+    pub kind: NodeKind,
+    /// First line of the node (1-indexed).
+    pub line: usize,
+    /// Last line of the node (1-indexed).
+    pub end_line: usize,
+    /// Tokens in source order. Together they cover the node without gaps.
+    pub tokens: Vec<Token>,
+}
 
-        // Parse key=value using zero-copy where possible
-        if let Some((key, value)) = content.split_once('=') {
-            let key = key.trim();
-            let value = value.trim();
+impl SyntaxNode {
+    /// Byte range of the node, including its line ending.
+    #[must_use]
+    pub fn span(&self) -> Range<usize> {
+        let start = self.tokens.first().map_or(0, |t| t.span.start);
+        let end = self.tokens.last().map_or(start, |t| t.span.end);
+        start..end
+    }
 
-            // Validate key format
-            if key.is_empty() {
-                continue;
+    /// Returns the first token of the given kind.
+    #[must_use]
+    pub fn token(&self, kind: TokenKind) -> Option<&Token> {
+        self.tokens.iter().find(|t| t.kind == kind)
+    }
+}
+
+/// Lossless concrete syntax tree of a .env file.
+///
+/// Every byte of the source belongs to exactly one token, so printing the tree
+/// reproduces the file byte-for-byte.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyntaxTree {
+    source: String,
+    nodes: Vec<SyntaxNode>,
+    /// Byte offset at which each physical line starts.
+    line_starts: Vec<usize>,
+}
+
+impl SyntaxTree {
+    /// Parses .env content with the default dialect.
+    #[must_use]
+    pub fn parse(content: &str) -> Self {
+        Self::parse_with_dialect(content, Dialect::default())
+    }
+
+    /// Parses .env content the way `dialect` reads it.
+    #[must_use]
+    pub fn parse_with_dialect(content: &str, dialect: Dialect) -> Self {
+        parse_syntax(content, dialect).0
+    }
+
+    /// Returns the parsed source.
+    #[must_use]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the nodes in source order.
+    #[must_use]
+    pub fn nodes(&self) -> &[SyntaxNode] {
+        &self.nodes
+    }
+
+    /// Returns the source text of a token.
+    #[must_use]
+    pub fn token_text(&self, token: &Token) -> &str {
+        &self.source[token.span.clone()]
+    }
+
+    /// Returns the source text of a node, including its line ending.
+    #[must_use]
+    pub fn node_text(&self, node: &SyntaxNode) -> &str {
+        &self.source[node.span()]
+    }
+
+    /// Returns the byte range of a physical line (1-indexed), without its line ending.
+    #[must_use]
+    pub fn line_span(&self, line: usize) -> Range<usize> {
+        let start = self
+            .line_starts
+            .get(line.saturating_sub(1))
+            .map_or(self.source.len(), |&s| s.min(self.source.len()));
+        start..line_bounds(&self.source, start).0
+    }
+
+    /// Returns the 1-indexed line and character column of a byte offset.
+    #[must_use]
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&start| start <= offset);
+        let line_start = self.line_starts.get(line.max(1) - 1).copied().unwrap_or(0);
+        let column = self.source[line_start..offset.min(self.source.len())]
+            .chars()
+            .count();
+        (line.max(1), column + 1)
+    }
+
+    /// Returns the key of an entry node.
+    #[must_use]
+    pub fn key(&self, node: &SyntaxNode) -> Option<&str> {
+        node.token(TokenKind::Key).map(|t| self.token_text(t))
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for node in &self.nodes {
+            for token in &node.tokens {
+                f.write_str(self.token_text(token))?;
             }
+        }
+        Ok(())
+    }
+}
 
-            // Remove quotes from value if present (requires allocation)
-            let value = if (value.starts_with('"') && value.ends_with('"'))
-                || (value.starts_with('\'') && value.ends_with('\''))
-            {
-                Cow::Owned(value[1..value.len() - 1].to_string())
+/// Parses the syntax tree and the variables of `content` in one pass.
+fn parse_syntax(content: &str, dialect: Dialect) -> (SyntaxTree, Vec<EnvVar>) {
+    let mut iter = EnvVarIter::with_dialect(content, dialect).peekable();
+    let mut nodes = Vec::new();
+    let mut vars = Vec::new();
+    let mut pos = 0;
+    let mut line = 0;
+
+    while pos < content.len() {
+        line += 1;
+        let mut tokens = Vec::new();
+
+        let node = if let Some(var) = iter.next_if(|v| v.line == line) {
+            // The node runs to the end of the line the value ends on
+            let (line_end, next) = line_bounds(content, var.raw_span.end);
+            entry_tokens(content, pos, &var, &mut tokens);
+            push_trimmed(content, var.raw_span.end..line_end, &mut tokens, |text| {
+                if text.starts_with('#') {
+                    TokenKind::InlineComment
+                } else {
+                    TokenKind::Invalid
+                }
+            });
+            push_token(&mut tokens, TokenKind::LineEnding, line_end..next);
+            pos = next;
+
+            let node = SyntaxNode {
+                kind: NodeKind::Entry,
+                line,
+                end_line: var.end_line,
+                tokens,
+            };
+            line = var.end_line;
+            vars.push(var.to_owned());
+            node
+        } else {
+            let (line_end, next) = line_bounds(content, pos);
+            let trimmed = content[pos..line_end].trim();
+            let kind = if trimmed.is_empty() {
+                NodeKind::Blank
+            } else if trimmed.starts_with('#') {
+                NodeKind::Comment
             } else {
-                Cow::Borrowed(value)
+                NodeKind::Invalid
             };
-
-            vars.push(EnvVar {
-                key: key.to_string(),      // Key always needs owned for downstream use
-                value: value.into_owned(), // Convert to owned for storage
-                line: line_num,
-                exported: is_exported,
-                quoted: false,
+            push_trimmed(content, pos..line_end, &mut tokens, |_| {
+                if kind == NodeKind::Comment {
+                    TokenKind::Comment
+                } else {
+                    TokenKind::Invalid
+                }
             });
-        }
+            push_token(&mut tokens, TokenKind::LineEnding, line_end..next);
+            pos = next;
+
+            SyntaxNode {
+                kind,
+                line,
+                end_line: line,
+                tokens,
+            }
+        };
+        nodes.push(node);
     }
 
-    vars
+    let line_starts = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let tree = SyntaxTree {
+        source: content.to_string(),
+        nodes,
+        line_starts,
+    };
+    (tree, vars)
 }
 
-/// High-performance iterator for parsing that avoids allocations
+/// Tokenizes an assignment from the start of its line to the end of its raw value.
+fn entry_tokens(content: &str, line_start: usize, var: &EnvVarRef<'_>, tokens: &mut Vec<Token>) {
+    let skip_whitespace = |from: usize| {
+        let rest = &content[from..];
+        from + rest.len() - rest.trim_start_matches([' ', '\t']).len()
+    };
+
+    let mut cursor = skip_whitespace(line_start);
+    push_token(tokens, TokenKind::Whitespace, line_start..cursor);
+
+    if var.exported {
+        let keyword_end = cursor + "export".len();
+        push_token(tokens, TokenKind::Export, cursor..keyword_end);
+        cursor = skip_whitespace(keyword_end);
+        push_token(tokens, TokenKind::Whitespace, keyword_end..cursor);
+    }
+
+    let Range {
+        start: key_start,
+        end: key_end,
+    } = var.key_span.clone();
+    push_token(tokens, TokenKind::Whitespace, cursor..key_start);
+    push_token(tokens, TokenKind::Key, key_start..key_end);
+    let operator = skip_whitespace(key_end);
+    push_token(tokens, TokenKind::Whitespace, key_end..operator);
+    push_token(tokens, TokenKind::Operator, operator..operator + 1);
+    push_token(
+        tokens,
+        TokenKind::Whitespace,
+        operator + 1..var.raw_span.start,
+    );
+
+    let Range { start, end } = var.raw_span.clone();
+    if var.quote == QuoteStyle::None {
+        push_token(tokens, TokenKind::Value, start..end);
+    } else {
+        push_token(tokens, TokenKind::Quote, start..start + 1);
+        push_token(tokens, TokenKind::Value, start + 1..end - 1);
+        push_token(tokens, TokenKind::Quote, end - 1..end);
+    }
+}
+
+/// Pushes `span` as leading whitespace, a token of `kind(text)` and trailing whitespace.
+fn push_trimmed(
+    content: &str,
+    span: Range<usize>,
+    tokens: &mut Vec<Token>,
+    kind: impl Fn(&str) -> TokenKind,
+) {
+    let text = &content[span.clone()];
+    let start = span.start + text.len() - text.trim_start().len();
+    let end = span.start + text.trim_end().len();
+
+    if start >= end {
+        push_token(tokens, TokenKind::Whitespace, span);
+        return;
+    }
+    push_token(tokens, TokenKind::Whitespace, span.start..start);
+    push_token(tokens, kind(&content[start..end]), start..end);
+    push_token(tokens, TokenKind::Whitespace, end..span.end);
+}
+
+fn push_token(tokens: &mut Vec<Token>, kind: TokenKind, span: Range<usize>) {
+    if !span.is_empty() {
+        tokens.push(Token { kind, span });
+    }
+}
+
+/// High-performance iterator for parsing that avoids allocations.
+///
+/// With the default [`Dialect`], values in double quotes may span several lines and
+/// have `\n`, `\r`, `\t`, `\"`, `\\` and `\$` escapes processed; single-quoted and
+/// backtick-quoted values are taken literally and may also span lines. Other
+/// dialects enable a subset of these features.
 pub struct EnvVarIter<'a> {
-    lines: std::str::Lines<'a>,
+    content: &'a str,
+    pos: usize,
     line_num: usize,
+    features: DialectFeatures,
 }
 
 impl<'a> EnvVarIter<'a> {
     #[must_use]
-    pub fn new(content: &'a str) -> Self {
+    pub const fn new(content: &'a str) -> Self {
+        Self::with_dialect(content, Dialect::Dotenv)
+    }
+
+    /// Creates an iterator that parses `content` the way `dialect` does.
+    #[must_use]
+    pub const fn with_dialect(content: &'a str, dialect: Dialect) -> Self {
         Self {
-            lines: content.lines(),
+            content,
+            pos: 0,
             line_num: 0,
+            features: dialect.features(),
         }
     }
+
+    fn quote_style(&self, c: char) -> Option<QuoteStyle> {
+        match QuoteStyle::from_char(c)? {
+            QuoteStyle::Backtick if !self.features.backticks => None,
+            _ if !self.features.quotes => None,
+            style => Some(style),
+        }
+    }
+
+    /// Parses a quoted value whose opening quote is at `open`. Returns the value
+    /// and the offset just past the closing quote, or `None` if it is unterminated.
+    fn quoted_value(
+        &self,
+        open: usize,
+        line_end: usize,
+        style: QuoteStyle,
+    ) -> Option<(Cow<'a, str>, usize)> {
+        let quote = style.as_char()?;
+        let body_start = open + quote.len_utf8();
+        let limit = if self.features.multiline {
+            self.content.len()
+        } else {
+            line_end
+        };
+        let body = &self.content[body_start..limit];
+
+        let mut escaped = false;
+        for (i, c) in body.char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' && quote == '"' {
+                escaped = true;
+            } else if c == quote {
+                let inner = &body[..i];
+                let value = if quote == '"' {
+                    unescape(inner, self.features.escapes)
+                } else {
+                    Cow::Borrowed(inner)
+                };
+                return Some((value, body_start + i + 1));
+            }
+        }
+
+        None
+    }
+
+    /// Joins an unquoted value ending in a backslash with the following lines.
+    /// Returns the value and the offset where its raw text ends.
+    fn continued_value(&mut self, first: &'a str, line_end: usize) -> (Cow<'a, str>, usize) {
+        let mut value = Cow::Borrowed(first);
+        let mut raw_end = line_end;
+
+        while value.ends_with('\\') && self.pos < self.content.len() {
+            let (next_end, next) = line_bounds(self.content, self.pos);
+            let continuation = self.content[self.pos..next_end].trim();
+
+            let joined = value.to_mut();
+            joined.pop();
+            joined.push_str(continuation);
+
+            raw_end = next_end;
+            self.pos = next;
+            self.line_num += 1;
+        }
+
+        (value, raw_end)
+    }
 }
 
 impl<'a> Iterator for EnvVarIter<'a> {
@@ -149,37 +605,142 @@ impl<'a> Iterator for EnvVarIter<'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            let line = self.lines.next()?;
+            if self.pos >= self.content.len() {
+                return None;
+            }
+
+            let line_start = self.pos;
+            let (line_end, next) = line_bounds(self.content, line_start);
+            self.pos = next;
             self.line_num += 1;
+
+            let line = &self.content[line_start..line_end];
             let trimmed = line.trim();
 
             if trimmed.is_empty() || trimmed.starts_with('#') {
                 continue;
             }
 
-            let (is_exported, content) = if let Some(stripped) = trimmed.strip_prefix("export ") {
-                (true, stripped.trim())
+            let (is_exported, content) = match trimmed.strip_prefix("export ") {
+                Some(stripped) if self.features.export => (true, stripped.trim()),
+                _ => (false, trimmed),
+            };
+
+            let Some((key, value)) = content.split_once('=') else {
+                continue;
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                continue;
+            }
+            let key_start = line_end - line.trim_start().len() + (trimmed.len() - content.len());
+
+            let start_line = self.line_num;
+            let after_space = value.starts_with([' ', '\t']);
+            let value = value.trim_start();
+            let value_start = line_end - line.trim_start().len() + (trimmed.len() - value.len());
+
+            let quote = value.chars().next().and_then(|c| self.quote_style(c));
+            let parsed =
+                quote.and_then(|q| self.quoted_value(value_start, line_end, q).map(|p| (q, p)));
+
+            let (quote, value, raw_end) = if let Some((quote, (value, close))) = parsed {
+                // The value may have spanned several lines; resume after the line
+                // holding the closing quote. Anything after the quote is a comment.
+                self.line_num += self.content[value_start..close].matches('\n').count();
+                let (_, next) = line_bounds(self.content, close);
+                self.pos = next;
+                (quote, value, close)
             } else {
-                (false, trimmed)
+                // Unquoted or unterminated: the rest of the line up to an inline comment.
+                let raw = strip_inline_comment(value, after_space, self.features.inline_comments);
+                if self.features.line_continuation && raw.ends_with('\\') {
+                    let (value, raw_end) = self.continued_value(raw, line_end);
+                    (QuoteStyle::None, value, raw_end)
+                } else {
+                    (
+                        QuoteStyle::None,
+                        Cow::Borrowed(raw),
+                        value_start + raw.len(),
+                    )
+                }
             };
 
-            if let Some((key, value)) = content.split_once('=') {
-                let key = key.trim();
-                let value = value.trim();
+            return Some(EnvVarRef {
+                key: Cow::Borrowed(key),
+                value,
+                line: start_line,
+                end_line: self.line_num,
+                exported: is_exported,
+                quote,
+                raw_value: &self.content[value_start..raw_end],
+                key_span: key_start..key_start + key.len(),
+                raw_span: value_start..raw_end,
+            });
+        }
+    }
+}
 
-                if key.is_empty() {
-                    continue;
-                }
+/// Returns the byte range of the physical line starting at `start`, without
+/// its line terminator, and the offset of the next line.
+fn line_bounds(content: &str, start: usize) -> (usize, usize) {
+    let rest = &content[start..];
+    let (end, next) = rest.find('\n').map_or((content.len(), content.len()), |i| {
+        (start + i, start + i + 1)
+    });
+    let end = if content[start..end].ends_with('\r') {
+        end - 1
+    } else {
+        end
+    };
+    (end, next)
+}
 
-                return Some(EnvVarRef {
-                    key: Cow::Borrowed(key),
-                    value: Cow::Borrowed(value),
-                    line: self.line_num,
-                    exported: is_exported,
-                });
-            }
+/// Cuts an unquoted value at its inline comment, if the dialect has them.
+/// `after_space` tells whether whitespace preceded the value itself.
+fn strip_inline_comment(value: &str, after_space: bool, mode: InlineComments) -> &str {
+    let mut prev_is_space = after_space;
+    for (i, c) in value.char_indices() {
+        let starts_comment = match mode {
+            InlineComments::None => false,
+            InlineComments::AfterWhitespace => prev_is_space,
+            InlineComments::Anywhere => true,
+        };
+        if c == '#' && starts_comment {
+            return value[..i].trim_end();
+        }
+        prev_is_space = c.is_whitespace();
+    }
+    value.trim_end()
+}
+
+/// Processes the escape sequences recognised inside double-quoted values.
+/// Unknown escapes are kept verbatim.
+fn unescape(raw: &str, mode: Escapes) -> Cow<'_, str> {
+    if mode == Escapes::None || !raw.contains('\\') {
+        return Cow::Borrowed(raw);
+    }
+
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match (chars.next(), mode) {
+            (Some('n'), _) => out.push('\n'),
+            (Some('r'), Escapes::Full) => out.push('\r'),
+            (Some('t'), Escapes::Full) => out.push('\t'),
+            (Some(c @ ('"' | '\\' | '$')), Escapes::Full) => out.push(c),
+            (Some(other), _) => {
+                out.push('\\');
+                out.push(other);
+            },
+            (None, _) => out.push('\\'),
         }
     }
+    Cow::Owned(out)
 }
 
 #[cfg(test)]
@@ -224,4 +785,206 @@ mod tests {
         let env = EnvFile::parse_content(PathBuf::from("test.env"), content).unwrap();
         assert_eq!(env.vars[0].value, "quoted value");
     }
+
+    #[test]
+    fn test_multiline_double_quoted() {
+        let content = "CERT=\"-----BEGIN CERT-----\nabc\n-----END CERT-----\"\nNEXT=1\n";
+        let env = EnvFile::parse_content(PathBuf::from("test.env"), content).unwrap();
+
+        assert_eq!(env.vars.len(), 2);
+        assert_eq!(
+            env.vars[0].value,
+            "-----BEGIN CERT-----\nabc\n-----END CERT-----"
+        );
+        assert_eq!(env.vars[0].line, 1);
+        assert_eq!(env.vars[0].end_line, 3);
+        assert_eq!(env.vars[1].key, "NEXT");
+        assert_eq!(env.vars[1].line, 4);
+        assert_eq!(
+            env.multiline_var_at(2).map(|v| v.key.as_str()),
+            Some("CERT")
+        );
+    }
+
+    #[test]
+    fn test_double_quoted_escapes() {
+        let content = r#"KEY="a\nb \"q\" \$HOME \\ \x""#;
+        let env = EnvFile::parse_content(PathBuf::from("test.env"), content).unwrap();
+        assert_eq!(env.vars[0].value, "a\nb \"q\" $HOME \\ \\x");
+    }
+
+    #[test]
+    fn test_single_and_backtick_quotes_are_literal() {
+        let content = "A='a\\nb $X'\nB=`it's \"fine\"`\nC='multi\nline'\n";
+        let env = EnvFile::parse_content(PathBuf::from("test.env"), content).unwrap();
+
+        assert_eq!(env.vars[0].value, "a\\nb $X");
+        assert_eq!(env.vars[1].value, "it's \"fine\"");
+        assert_eq!(env.vars[2].value, "multi\nline");
+        assert_eq!(env.vars[2].end_line, 4);
+    }
+
+    #[test]
+    fn test_unterminated_quote_is_literal() {
+        let content = "KEY=\"oops\nOTHER=1\n";
+        let env = EnvFile::parse_content(PathBuf::from("test.env"), content).unwrap();
+        assert_eq!(env.vars.len(), 2);
+        assert_eq!(env.vars[0].value, "\"oops");
+        assert_eq!(env.vars[0].quote, QuoteStyle::None);
+        assert_eq!(env.vars[1].key, "OTHER");
+    }
+
+    #[test]
+    fn test_inline_comments() {
+        let content = "A=value # note\nB=\"has # hash\" # note\nC=url#fragment\nD= # only a comment\nE='x'#c\n";
+        let env = EnvFile::parse_content(PathBuf::from("test.env"), content).unwrap();
+
+        assert_eq!(env.vars[0].value, "value");
+        assert_eq!(env.vars[0].raw_value, "value");
+        assert_eq!(env.vars[1].value, "has # hash");
+        assert_eq!(env.vars[1].raw_value, "\"has # hash\"");
+        assert_eq!(env.vars[2].value, "url#fragment");
+        assert_eq!(env.vars[3].value, "");
+        assert_eq!(env.vars[4].value, "x");
+    }
+
+    #[test]
+    fn test_quote_metadata() {
+        let content = "A=plain\nB='single'\nC=\"double\"\nD=`tick`\n";
+        let env = EnvFile::parse_content(PathBuf::from("test.env"), content).unwrap();
+
+        let styles: Vec<_> = env.vars.iter().map(|v| v.quote).collect();
+        assert_eq!(
+            styles,
+            [
+                QuoteStyle::None,
+                QuoteStyle::Single,
+                QuoteStyle::Double,
+                QuoteStyle::Backtick
+            ]
+        );
+        assert!(!env.vars[0].quoted);
+        assert!(env.vars[1].quoted);
+        assert_eq!(env.vars[3].raw_value, "`tick`");
+    }
+
+    #[test]
+    fn test_docker_dialect_is_literal() {
+        let content = "export A=1\nB=\"quoted\" # note\n";
+        let env = EnvFile::parse_content_with_dialect(
+            PathBuf::from("test.env"),
+            content,
+            Dialect::Docker,
+        )
+        .unwrap();
+
+        assert_eq!(env.vars[0].key, "export A");
+        assert!(!env.vars[0].exported);
+        assert_eq!(env.vars[1].value, "\"quoted\" # note");
+        assert_eq!(env.vars[1].quote, QuoteStyle::None);
+    }
+
+    #[test]
+    fn test_systemd_line_continuation() {
+        let content = "A=one \\\n  two\nB=\"x\" # not a comment\n";
+        let env = EnvFile::parse_content_with_dialect(
+            PathBuf::from("test.env"),
+            content,
+            Dialect::Systemd,
+        )
+        .unwrap();
+
+        assert_eq!(env.vars[0].value, "one two");
+        assert_eq!(env.vars[0].end_line, 2);
+        assert_eq!(env.vars[1].key, "B");
+        assert_eq!(env.vars[1].line, 3);
+    }
+
+    #[test]
+    fn test_node_dialect_comments_and_escapes() {
+        let content = "A=url#fragment\nB=\"tab\\there\\n\"\n";
+        let env = EnvFile::parse_content_with_dialect(
+            PathBuf::from("test.env"),
+            content,
+            Dialect::NodeDotenv,
+        )
+        .unwrap();
+
+        assert_eq!(env.vars[0].value, "url");
+        assert_eq!(env.vars[1].value, "tab\\there\n");
+    }
+
+    #[test]
+    fn test_syntax_tree_round_trip() {
+        let content = "# header\r\n\n  export A = \"one\n two\" # note\nB='x'trailing\n=oops\nnot an assignment  \nC=value\t# c\nD=";
+        let tree = SyntaxTree::parse(content);
+
+        assert_eq!(tree.to_string(), content);
+        let kinds: Vec<_> = tree.nodes().iter().map(|n| n.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                NodeKind::Comment,
+                NodeKind::Blank,
+                NodeKind::Entry,
+                NodeKind::Entry,
+                NodeKind::Invalid,
+                NodeKind::Invalid,
+                NodeKind::Entry,
+                NodeKind::Entry,
+            ]
+        );
+
+        // Tokens cover the source without gaps or overlaps
+        let mut end = 0;
+        for token in tree.nodes().iter().flat_map(|n| &n.tokens) {
+            assert_eq!(token.span.start, end);
+            end = token.span.end;
+        }
+        assert_eq!(end, content.len());
+    }
+
+    #[test]
+    fn test_syntax_tree_entry_tokens() {
+        let tree = SyntaxTree::parse("  export A = \"one\n two\" # note\nNEXT=1\n");
+        let node = &tree.nodes()[0];
+        let tokens: Vec<_> = node
+            .tokens
+            .iter()
+            .map(|t| (t.kind, tree.token_text(t)))
+            .collect();
+
+        assert_eq!(
+            tokens,
+            [
+                (TokenKind::Whitespace, "  "),
+                (TokenKind::Export, "export"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Key, "A"),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Operator, "="),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::Quote, "\""),
+                (TokenKind::Value, "one\n two"),
+                (TokenKind::Quote, "\""),
+                (TokenKind::Whitespace, " "),
+                (TokenKind::InlineComment, "# note"),
+                (TokenKind::LineEnding, "\n"),
+            ]
+        );
+        assert_eq!((node.line, node.end_line), (1, 2));
+        assert_eq!(tree.key(&tree.nodes()[1]), Some("NEXT"));
+        assert_eq!(tree.nodes()[1].line, 3);
+    }
+
+    #[test]
+    fn test_syntax_tree_positions() {
+        let tree = SyntaxTree::parse("A=1\r\nBé=2  \n");
+
+        assert_eq!(tree.line_span(2), 5..12);
+        assert_eq!(tree.position(0), (1, 1));
+        assert_eq!(tree.position(5), (2, 1));
+        assert_eq!(tree.position(8), (2, 3));
+        assert_eq!(tree.position(13), (3, 1));
+    }
 }
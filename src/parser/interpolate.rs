@@ -0,0 +1,435 @@
+//! Variable interpolation for .env values.
+//!
+//! Supports `$VAR`, `${VAR}`, `${VAR:-default}`, `${VAR-default}`, `${VAR:?message}`,
+//! `${VAR?message}`, `${VAR:+alternative}` and `${VAR+alternative}`. Unquoted and
+//! double-quoted values are interpolated; single-quoted and backtick values are literal.
+//!
+//! References are resolved top-down, the way dotenv loaders do: a value can only see
+//! keys defined above it, optionally falling back to the process environment. Dialects
+//! that do not interpolate, or only expand `${VAR}`, are honoured.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::parser::dialect::{Escapes, Interpolation};
+use crate::parser::{Dialect, EnvFile, EnvVar, QuoteStyle};
+
+/// The operator used inside a `${...}` reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    /// `$VAR` or `${VAR}`.
+    None,
+    /// `${VAR:-default}` or `${VAR-default}`.
+    Default,
+    /// `${VAR:?message}` or `${VAR?message}`.
+    Required,
+    /// `${VAR:+alternative}` or `${VAR+alternative}`.
+    Alternative,
+}
+
+/// A reference to another variable inside a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// Name of the referenced variable.
+    pub name: String,
+    /// Operator applied to the reference.
+    pub modifier: Modifier,
+    /// True for the `:` forms, which treat an empty value like an unset one.
+    pub colon: bool,
+    /// Default, message or alternative text, as written.
+    pub argument: String,
+    /// Byte range of the reference within [`EnvVar::raw_value`].
+    pub span: Range<usize>,
+}
+
+impl Reference {
+    /// Returns true if the reference expands to something even when the variable is unset.
+    #[must_use]
+    pub const fn has_fallback(&self) -> bool {
+        matches!(self.modifier, Modifier::Default | Modifier::Alternative)
+    }
+}
+
+#[derive(Debug)]
+enum Segment {
+    Text(String),
+    Ref(Reference),
+}
+
+/// Returns every reference `dialect` expands in a variable's value, including
+/// ones nested in defaults.
+#[must_use]
+pub fn references(var: &EnvVar, dialect: Dialect) -> Vec<Reference> {
+    let Some(body) = Body::of(var, dialect) else {
+        return Vec::new();
+    };
+
+    let mut refs = Vec::new();
+    collect_references(&body.scan(), body.bare, &mut refs);
+    refs
+}
+
+/// Expands a variable's value the way `dialect` does, looking referenced names up
+/// with `lookup`.
+#[must_use]
+pub fn expand(var: &EnvVar, dialect: Dialect, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    match Body::of(var, dialect) {
+        Some(body) => expand_segments(&body.scan(), body.bare, lookup),
+        None => var.value.clone(),
+    }
+}
+
+/// Resolves all values of an [`EnvFile`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Interpolator {
+    process_env: bool,
+}
+
+impl Interpolator {
+    /// Creates an interpolator that only resolves references within the file.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { process_env: false }
+    }
+
+    /// Also resolve references to keys the file does not define from the process environment.
+    #[must_use]
+    pub const fn with_process_env(mut self, enabled: bool) -> Self {
+        self.process_env = enabled;
+        self
+    }
+
+    /// Returns the expanded value of every key. Later definitions of a key win.
+    #[must_use]
+    pub fn resolve(&self, file: &EnvFile) -> HashMap<String, String> {
+        file.vars
+            .iter()
+            .map(|var| var.key.clone())
+            .zip(self.resolve_vars(file))
+            .collect()
+    }
+
+    /// Returns the expanded value of each entry of [`EnvFile::vars`], in order.
+    #[must_use]
+    pub fn resolve_vars(&self, file: &EnvFile) -> Vec<String> {
+        let mut resolved: HashMap<&str, String> = HashMap::new();
+        let mut values = Vec::with_capacity(file.vars.len());
+
+        for var in &file.vars {
+            let value = expand(var, file.dialect, &|name| {
+                resolved.get(name).cloned().or_else(|| {
+                    if self.process_env {
+                        std::env::var(name).ok()
+                    } else {
+                        None
+                    }
+                })
+            });
+            resolved.insert(&var.key, value.clone());
+            values.push(value);
+        }
+
+        values
+    }
+}
+
+/// The interpolatable part of a value.
+struct Body<'a> {
+    text: &'a str,
+    /// Offset of `text` in `raw_value`.
+    base: usize,
+    escapes: Escapes,
+    /// Whether `$VAR` without braces is a reference.
+    bare: bool,
+}
+
+impl<'a> Body<'a> {
+    fn of(var: &'a EnvVar, dialect: Dialect) -> Option<Self> {
+        let features = dialect.features();
+        let bare = match features.interpolation {
+            Interpolation::None => return None,
+            Interpolation::BracesOnly => false,
+            Interpolation::Full => true,
+        };
+
+        let (text, base, escapes) = match var.quote {
+            QuoteStyle::None => (var.raw_value.as_str(), 0, Escapes::None),
+            QuoteStyle::Double => (
+                &var.raw_value[1..var.raw_value.len() - 1],
+                1,
+                features.escapes,
+            ),
+            QuoteStyle::Single | QuoteStyle::Backtick => return None,
+        };
+
+        Some(Self {
+            text,
+            base,
+            escapes,
+            bare,
+        })
+    }
+
+    fn scan(&self) -> Vec<Segment> {
+        scan(self.text, self.base, self.escapes, self.bare)
+    }
+}
+
+fn scan(text: &str, base: usize, escapes: Escapes, bare: bool) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut i = 0;
+
+    while let Some(c) = text[i..].chars().next() {
+        if c == '\\' {
+            let next = text[i + 1..].chars().next();
+            match (next, escapes) {
+                (Some('$'), _) => literal.push('$'),
+                (Some('n'), Escapes::Full | Escapes::NewlineOnly) => literal.push('\n'),
+                (Some('r'), Escapes::Full) => literal.push('\r'),
+                (Some('t'), Escapes::Full) => literal.push('\t'),
+                (Some(c @ ('"' | '\\')), Escapes::Full) => literal.push(c),
+                (Some(other), _) => {
+                    literal.push('\\');
+                    literal.push(other);
+                },
+                (None, _) => {
+                    literal.push('\\');
+                    break;
+                },
+            }
+            i += 1 + next.map_or(0, char::len_utf8);
+            continue;
+        }
+
+        if c == '$' {
+            if let Some((reference, end)) = parse_reference(text, i, base, bare) {
+                if !literal.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Ref(reference));
+                i = end;
+                continue;
+            }
+        }
+
+        literal.push(c);
+        i += c.len_utf8();
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Text(literal));
+    }
+    segments
+}
+
+/// Parses the reference starting at the `$` at `start`. Returns it and the offset after it.
+fn parse_reference(
+    text: &str,
+    start: usize,
+    base: usize,
+    bare: bool,
+) -> Option<(Reference, usize)> {
+    let rest = &text[start + 1..];
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let Some(braced) = rest.strip_prefix('{') else {
+        if !bare || !rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            return None;
+        }
+        let name_len = rest.find(|c| !is_name(c)).unwrap_or(rest.len());
+        let end = start + 1 + name_len;
+        return Some((
+            Reference {
+                name: rest[..name_len].to_string(),
+                modifier: Modifier::None,
+                colon: false,
+                argument: String::new(),
+                span: base + start..base + end,
+            },
+            end,
+        ));
+    };
+
+    let mut depth = 1;
+    let close = braced.char_indices().find_map(|(i, c)| {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            _ => {},
+        }
+        (depth == 0).then_some(i)
+    })?;
+
+    let inner = &braced[..close];
+    let name_len = inner.find(|c| !is_name(c)).unwrap_or(inner.len());
+    if name_len == 0 {
+        return None;
+    }
+
+    let operator = &inner[name_len..];
+    let colon = operator.starts_with(':');
+    let (modifier, op_len) = match operator.trim_start_matches(':').chars().next() {
+        None if !colon => (Modifier::None, 0),
+        Some('-') => (Modifier::Default, 1),
+        Some('?') => (Modifier::Required, 1),
+        Some('+') => (Modifier::Alternative, 1),
+        _ => return None,
+    };
+    let op_len = op_len + usize::from(colon);
+
+    let end = start + 2 + close + 1;
+    Some((
+        Reference {
+            name: inner[..name_len].to_string(),
+            modifier,
+            colon,
+            argument: inner[name_len + op_len..].to_string(),
+            span: base + start..base + end,
+        },
+        end,
+    ))
+}
+
+fn collect_references(segments: &[Segment], bare: bool, refs: &mut Vec<Reference>) {
+    for segment in segments {
+        if let Segment::Ref(reference) = segment {
+            refs.push(reference.clone());
+
+            // References nested in a default or alternative
+            let argument_start = reference.span.end - 1 - reference.argument.len();
+            let nested = scan(&reference.argument, argument_start, Escapes::None, bare);
+            collect_references(&nested, bare, refs);
+        }
+    }
+}
+
+fn expand_segments(
+    segments: &[Segment],
+    bare: bool,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> String {
+    let mut out = String::new();
+
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Ref(reference) => {
+                let current = lookup(&reference.name);
+                let is_set = current
+                    .as_ref()
+                    .is_some_and(|v| !(reference.colon && v.is_empty()));
+                let argument = || {
+                    let nested = scan(&reference.argument, 0, Escapes::None, bare);
+                    expand_segments(&nested, bare, lookup)
+                };
+
+                match reference.modifier {
+                    Modifier::None | Modifier::Required => {
+                        out.push_str(current.as_deref().unwrap_or_default());
+                    },
+                    Modifier::Default if is_set => {
+                        out.push_str(current.as_deref().unwrap_or_default());
+                    },
+                    Modifier::Default => out.push_str(&argument()),
+                    Modifier::Alternative if is_set => out.push_str(&argument()),
+                    Modifier::Alternative => {},
+                }
+            },
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn parse(content: &str) -> EnvFile {
+        EnvFile::parse_content(PathBuf::from("test.env"), content).unwrap()
+    }
+
+    #[test]
+    fn test_reference_forms() {
+        let env = parse("A=$HOST:${PORT} ${USER:-guest} ${TOKEN:?missing} ${X+alt}\n");
+        let refs = references(&env.vars[0], Dialect::Dotenv);
+
+        let names: Vec<_> = refs.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["HOST", "PORT", "USER", "TOKEN", "X"]);
+        assert_eq!(refs[2].modifier, Modifier::Default);
+        assert!(refs[2].colon);
+        assert_eq!(refs[2].argument, "guest");
+        assert_eq!(refs[3].modifier, Modifier::Required);
+        assert_eq!(refs[4].modifier, Modifier::Alternative);
+        assert!(!refs[4].colon);
+        assert_eq!(&env.vars[0].raw_value[refs[1].span.clone()], "${PORT}");
+    }
+
+    #[test]
+    fn test_nested_default_references() {
+        let env = parse("A=${PRIMARY:-${FALLBACK}}\n");
+        let names: Vec<_> = references(&env.vars[0], Dialect::Dotenv)
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(names, ["PRIMARY", "FALLBACK"]);
+    }
+
+    #[test]
+    fn test_literal_quotes_and_escapes_are_not_references() {
+        let env = parse("A='$HOME'\nB=\"\\$HOME\"\nC=`$HOME`\nD=price$ 5\n");
+        for var in &env.vars {
+            assert!(references(var, Dialect::Dotenv).is_empty(), "{}", var.key);
+        }
+    }
+
+    #[test]
+    fn test_resolve_top_down() {
+        let env = parse(
+            "HOST=db\nPORT=5432\nURL=\"postgres://${HOST}:$PORT/${NAME:-app}\"\nEARLY=${LATE}\nLATE=x\nEMPTY=\nALT=${EMPTY:+set}${EMPTY+set}\nESC=\"\\$HOST\"\n",
+        );
+        let resolved = Interpolator::new().resolve(&env);
+
+        assert_eq!(resolved["URL"], "postgres://db:5432/app");
+        assert_eq!(resolved["EARLY"], "");
+        assert_eq!(resolved["ALT"], "set");
+        assert_eq!(resolved["ESC"], "$HOST");
+        assert_eq!(env.resolved_value("URL"), Some("postgres://db:5432/app"));
+    }
+
+    #[test]
+    fn test_dialect_interpolation() {
+        let content = "HOST=db\nA=$HOST/${HOST}\n";
+
+        let python = EnvFile::parse_content_with_dialect(
+            PathBuf::from("test.env"),
+            content,
+            Dialect::PythonDotenv,
+        )
+        .unwrap();
+        assert_eq!(Interpolator::new().resolve(&python)["A"], "$HOST/db");
+
+        let node = EnvFile::parse_content_with_dialect(
+            PathBuf::from("test.env"),
+            content,
+            Dialect::NodeDotenv,
+        )
+        .unwrap();
+        assert!(references(&node.vars[1], Dialect::NodeDotenv).is_empty());
+        assert_eq!(Interpolator::new().resolve(&node)["A"], "$HOST/${HOST}");
+    }
+
+    #[test]
+    fn test_resolve_from_process_env() {
+        let env = parse("A=${CARGO_PKG_NAME}\n");
+
+        assert_eq!(Interpolator::new().resolve(&env)["A"], "");
+        assert_eq!(
+            Interpolator::new().with_process_env(true).resolve(&env)["A"],
+            "envcheck"
+        );
+    }
+}
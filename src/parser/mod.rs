@@ -2,11 +2,16 @@
 
 pub mod ansible;
 pub mod argocd;
+pub mod dialect;
 pub mod env;
 pub mod github_actions;
 pub mod helm;
+pub mod interpolate;
 pub mod k8s;
+pub mod optional;
+pub mod section;
 pub mod terraform;
 
-pub use env::{EnvFile, EnvVar};
+pub use dialect::Dialect;
+pub use env::{EnvFile, EnvVar, QuoteStyle, SyntaxTree};
 pub use k8s::{K8sEnvRef, K8sManifest, K8sRefSource};
@@ -0,0 +1,89 @@
+//! Optional-key markers in reference files such as `.env.example`.
+//!
+//! A key is optional if a `# @optional` comment sits directly above it or after
+//! its value, or if it only appears commented out, as in `# KEY=value`.
+
+use std::collections::BTreeSet;
+
+use crate::parser::env::{NodeKind, TokenKind};
+use crate::parser::EnvFile;
+
+const MARKER: &str = "@optional";
+
+impl EnvFile {
+    /// Returns the keys this file marks as optional.
+    #[must_use]
+    pub fn optional_keys(&self) -> BTreeSet<String> {
+        let tree = &self.syntax;
+        let mut keys = BTreeSet::new();
+        let mut marked = false;
+
+        for node in tree.nodes() {
+            match node.kind {
+                NodeKind::Comment => {
+                    let text = tree.node_text(node);
+                    if text.contains(MARKER) {
+                        marked = true;
+                    } else if let Some(key) = commented_key(text) {
+                        keys.insert(key.to_string());
+                    }
+                },
+                NodeKind::Entry => {
+                    let inline = node
+                        .token(TokenKind::InlineComment)
+                        .is_some_and(|t| tree.token_text(t).contains(MARKER));
+                    if marked || inline {
+                        if let Some(key) = tree.key(node) {
+                            keys.insert(key.to_string());
+                        }
+                    }
+                    marked = false;
+                },
+                NodeKind::Blank | NodeKind::Invalid => marked = false,
+            }
+        }
+
+        keys
+    }
+}
+
+/// Returns the key of a commented-out assignment like `# KEY=value`.
+fn commented_key(comment: &str) -> Option<&str> {
+    let rest = comment.trim_start().strip_prefix('#')?.trim_start();
+    let rest = rest.strip_prefix("export ").map_or(rest, str::trim_start);
+
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(rest.len());
+    let (key, after) = rest.split_at(end);
+    let starts_like_key = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    (starts_like_key && after.trim_start().starts_with('=')).then_some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_optional_markers() {
+        let content = "# @optional: local only\nDEBUG=\nPORT=80\n\
+                       # export SENTRY_DSN=\n# See docs\nCACHE= # @optional\n\
+                       # @optional\n\nAFTER_BLANK=1\n";
+        let file = EnvFile::parse_content(PathBuf::from(".env.example"), content).unwrap();
+
+        let keys: Vec<String> = file.optional_keys().into_iter().collect();
+        assert_eq!(keys, ["CACHE", "DEBUG", "SENTRY_DSN"]);
+    }
+
+    #[test]
+    fn test_commented_key() {
+        assert_eq!(commented_key("# API_KEY=secret"), Some("API_KEY"));
+        assert_eq!(commented_key("#FOO = bar"), Some("FOO"));
+        assert_eq!(commented_key("# Set this to true"), None);
+        assert_eq!(commented_key("# 1=2"), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! Sections of a .env file: groups of entries separated by blank lines or
+//! `# --- Section ---` comments.
+//!
+//! Sorting (`envcheck fix` and W003) only reorders entries within a section.
+
+use crate::parser::env::{NodeKind, SyntaxNode, SyntaxTree};
+
+/// An entry and the comments directly above it, as indices into [`SyntaxTree::nodes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionEntry {
+    /// Comment (and invalid) lines between the previous entry and this one.
+    pub comments: Vec<usize>,
+    pub node: usize,
+}
+
+/// A group of entries that is sorted independently of the others.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Section {
+    /// Blank lines and separators before the first entry, with anything above
+    /// them, and the comment block that opens the section.
+    pub header: Vec<usize>,
+    pub entries: Vec<SectionEntry>,
+    /// Comments after the last entry that are not followed by another entry.
+    pub trailer: Vec<usize>,
+}
+
+/// Returns true for separator comments such as `# --- Database ---` or `# ====`.
+#[must_use]
+pub fn is_separator(text: &str) -> bool {
+    let rest = text.trim().trim_start_matches('#').trim_start();
+    rest.starts_with("---") || rest.starts_with("===")
+}
+
+/// Splits a syntax tree into sections. Every node belongs to exactly one section.
+#[must_use]
+pub fn sections(tree: &SyntaxTree) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let mut current = Section::default();
+    let mut pending = Vec::new();
+
+    for (i, node) in tree.nodes().iter().enumerate() {
+        if starts_section(tree, node) && !current.entries.is_empty() {
+            current.trailer = std::mem::take(&mut pending);
+            sections.push(settle_header(std::mem::take(&mut current)));
+        }
+
+        if node.kind == NodeKind::Entry {
+            if current.entries.is_empty() {
+                // Comments below the last blank line or separator describe the entry
+                let split = pending
+                    .iter()
+                    .rposition(|&n| starts_section(tree, &tree.nodes()[n]))
+                    .map_or(0, |p| p + 1);
+                current.header = pending.drain(..split).collect();
+            }
+            current.entries.push(SectionEntry {
+                comments: std::mem::take(&mut pending),
+                node: i,
+            });
+        } else {
+            pending.push(i);
+        }
+    }
+
+    if current.entries.is_empty() {
+        current.header = pending;
+    } else {
+        current.trailer = pending;
+    }
+    if !current.header.is_empty() || !current.entries.is_empty() {
+        sections.push(settle_header(current));
+    }
+
+    sections
+}
+
+/// Moves the comments above the first entry into the header: a comment block that
+/// opens a section describes the section. When the other entries have comments
+/// of their own, the block describes the first entry and stays with it.
+fn settle_header(mut section: Section) -> Section {
+    let commented = section
+        .entries
+        .iter()
+        .skip(1)
+        .any(|e| !e.comments.is_empty());
+    if let Some(first) = section.entries.first_mut().filter(|_| !commented) {
+        section.header.append(&mut first.comments);
+    }
+    section
+}
+
+fn starts_section(tree: &SyntaxTree, node: &SyntaxNode) -> bool {
+    match node.kind {
+        NodeKind::Blank => true,
+        NodeKind::Comment => is_separator(tree.node_text(node)),
+        NodeKind::Entry | NodeKind::Invalid => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(tree: &SyntaxTree, section: &Section) -> Vec<String> {
+        section
+            .entries
+            .iter()
+            .filter_map(|e| tree.key(&tree.nodes()[e.node]).map(String::from))
+            .collect()
+    }
+
+    #[test]
+    fn test_blank_lines_and_separators_split_sections() {
+        let tree =
+            SyntaxTree::parse("# App\nB=1\nA=2\n\n# c\nC=3\n# --- Db ---\nE=5\nD=4\n# end\n");
+        let sections = sections(&tree);
+
+        assert_eq!(sections.len(), 3);
+        assert_eq!(keys(&tree, &sections[0]), ["B", "A"]);
+        assert_eq!(sections[0].header, [0]);
+        assert!(sections[0].entries[0].comments.is_empty());
+        assert_eq!(sections[1].header, [3, 4]);
+        assert!(sections[1].entries[0].comments.is_empty());
+        assert_eq!(keys(&tree, &sections[1]), ["C"]);
+        assert_eq!(sections[2].header, [6]);
+        assert_eq!(keys(&tree, &sections[2]), ["E", "D"]);
+        assert_eq!(sections[2].trailer, [9]);
+    }
+
+    #[test]
+    fn test_comments_attach_to_next_entry() {
+        let tree = SyntaxTree::parse("A=1\n# about b\nB=2\n");
+        let sections = sections(&tree);
+
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].entries[1].comments, [1]);
+
+        // With comments on the other entries, the first block belongs to its entry
+        let tree = SyntaxTree::parse("# about a\nA=1\n# about b\nB=2\n");
+        let section = &super::sections(&tree)[0];
+
+        assert!(section.header.is_empty());
+        assert_eq!(section.entries[0].comments, [0]);
+        assert!(is_separator("#=== Cache ==="));
+        assert!(!is_separator("# -- note"));
+    }
+}
@@ -0,0 +1,117 @@
+use regex::Regex;
+
+use crate::config::CustomRuleConfig;
+use crate::error::{EnvCheckError, Result};
+use crate::parser::interpolate::Interpolator;
+use crate::parser::EnvFile;
+use crate::rules::{Diagnostic, Rule, RuleId, Severity, Span};
+
+/// A rule declared under `rules.custom` in `.envcheckrc`.
+#[derive(Debug, Clone)]
+pub struct CustomRule {
+    id: String,
+    /// Glob patterns for the files the rule applies to; empty for all files.
+    pub files: Vec<String>,
+    severity: Severity,
+    key: Option<glob::Pattern>,
+    must_match: Option<Regex>,
+    must_not_match: Option<Regex>,
+    message: Option<String>,
+}
+
+impl CustomRule {
+    /// Builds the `index`th rule from its configuration. Fails on an empty ID
+    /// or an invalid severity, glob or regex.
+    pub fn new(index: usize, config: &CustomRuleConfig) -> Result<Self> {
+        let id = config.id.trim();
+        if id.is_empty() {
+            return Err(EnvCheckError::invalid_config(
+                format!("rules.custom[{index}].id"),
+                "a rule ID is required",
+            ));
+        }
+        let setting = |field: &str| format!("rules.custom[{id}].{field}");
+        let regex = |field: &str, pattern: &Option<String>| {
+            pattern
+                .as_deref()
+                .map(|p| {
+                    Regex::new(p).map_err(|e| EnvCheckError::invalid_config(setting(field), e))
+                })
+                .transpose()
+        };
+
+        Ok(Self {
+            id: id.to_string(),
+            files: config.files.clone(),
+            severity: config
+                .severity
+                .as_deref()
+                .map(str::parse)
+                .transpose()
+                .map_err(|e| EnvCheckError::invalid_config(setting("severity"), e))?
+                .unwrap_or(Severity::Warning),
+            key: config
+                .key
+                .as_deref()
+                .map(glob::Pattern::new)
+                .transpose()
+                .map_err(|e| EnvCheckError::invalid_config(setting("key"), e))?,
+            must_match: regex("must_match", &config.must_match)?,
+            must_not_match: regex("must_not_match", &config.must_not_match)?,
+            message: config.message.clone(),
+        })
+    }
+
+    /// Returns true if `value` breaks the rule.
+    fn violates(&self, value: &str) -> bool {
+        match (&self.must_match, &self.must_not_match) {
+            (None, None) => true,
+            (must, must_not) => {
+                must.as_ref().is_some_and(|re| !re.is_match(value))
+                    || must_not.as_ref().is_some_and(|re| re.is_match(value))
+            },
+        }
+    }
+}
+
+impl Rule for CustomRule {
+    fn id(&self) -> RuleId {
+        RuleId::Custom(self.id.clone())
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let key_only = self.must_match.is_none() && self.must_not_match.is_none();
+        let values = Interpolator::new().resolve_vars(env_file);
+        let mut diagnostics = Vec::new();
+
+        for (var, value) in env_file.vars.iter().zip(&values) {
+            if !self.key.as_ref().map_or(true, |p| p.matches(&var.key)) || !self.violates(value) {
+                continue;
+            }
+
+            let message = self.message.as_ref().map_or_else(
+                || format!("Key '{}' violates rule {}", var.key, self.id),
+                |message| message.replace("{key}", &var.key),
+            );
+            let span = if key_only {
+                var.key_span.clone()
+            } else {
+                var.raw_span.clone()
+            };
+
+            diagnostics.push(Diagnostic {
+                id: self.id(),
+                severity: self.severity,
+                message,
+                path: env_file.path.clone(),
+                line: Some(var.line),
+                span: Some(Span::new(env_file, span)),
+                labels: Vec::new(),
+                constraint: None,
+                fix: None,
+            });
+        }
+
+        diagnostics
+    }
+}
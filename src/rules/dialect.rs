@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+
+use crate::parser::interpolate::{references, Interpolator};
+use crate::parser::{Dialect, EnvFile, EnvVar, QuoteStyle};
+use crate::rules::{Diagnostic, Fix, Rule, RuleId, Severity};
+
+/// Reports lines the selected dialect reads differently from common dotenv semantics.
+pub struct DialectMismatchRule;
+
+impl DialectMismatchRule {
+    /// Explains why `dialect` reads `expected` differently.
+    fn reason(dialect: Dialect, expected: &EnvVar, actual: Option<&EnvVar>) -> &'static str {
+        let features = dialect.features();
+
+        if expected.exported && !features.export {
+            "`export` is not supported"
+        } else if features.line_continuation && expected.raw_value.ends_with('\\') {
+            "a trailing backslash continues the value on the next line"
+        } else if expected.is_multiline() && !features.multiline {
+            "multi-line values are not supported"
+        } else if (expected.quote != QuoteStyle::None && !features.quotes)
+            || (expected.quote == QuoteStyle::Backtick && !features.backticks)
+        {
+            "quotes are kept literally"
+        } else if actual.is_some_and(|a| a.raw_value != expected.raw_value) {
+            "inline comments are handled differently"
+        } else if !references(expected, Dialect::Dotenv).is_empty() {
+            "variables are expanded differently"
+        } else {
+            "escape sequences are handled differently"
+        }
+    }
+}
+
+impl Rule for DialectMismatchRule {
+    fn id(&self) -> RuleId {
+        RuleId::W009
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let dialect = env_file.dialect;
+        if dialect == Dialect::Dotenv {
+            return Vec::new();
+        }
+
+        let Ok(dotenv) = EnvFile::parse_content_with_dialect(
+            env_file.path.clone(),
+            env_file.syntax.source(),
+            Dialect::Dotenv,
+        ) else {
+            return Vec::new();
+        };
+
+        let expected_values = Interpolator::new().resolve_vars(&dotenv);
+        let actual_values = Interpolator::new().resolve_vars(env_file);
+        let actual: HashMap<usize, (&EnvVar, &String)> = env_file
+            .vars
+            .iter()
+            .zip(&actual_values)
+            .map(|(var, value)| (var.line, (var, value)))
+            .collect();
+
+        let mut diagnostics = Vec::new();
+
+        for (var, expected_value) in dotenv.vars.iter().zip(&expected_values) {
+            let found = actual.get(&var.line).copied();
+            let message = match found {
+                Some((other, _)) if other.key != var.key => format!(
+                    "{dialect} reads key '{}' as '{}': {}",
+                    var.key,
+                    other.key,
+                    Self::reason(dialect, var, Some(other))
+                ),
+                // The values themselves may be secrets, so only say why they differ
+                Some((other, value)) if value != expected_value => format!(
+                    "{dialect} reads '{}' differently: {}",
+                    var.key,
+                    Self::reason(dialect, var, Some(other))
+                ),
+                Some(_) => continue,
+                None => format!(
+                    "{dialect} does not read '{}' as written: {}",
+                    var.key,
+                    Self::reason(dialect, var, None)
+                ),
+            };
+
+            diagnostics.push(Diagnostic {
+                id: self.id(),
+                severity: Severity::Warning,
+                message,
+                path: env_file.path.clone(),
+                line: Some(var.line),
+                span: None,
+                labels: Vec::new(),
+                constraint: None,
+                fix: (var.exported && !dialect.features().export)
+                    .then(|| Fix::remove_export(env_file, var.line))
+                    .flatten(),
+            });
+        }
+
+        diagnostics
+    }
+}
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use crate::parser::EnvFile;
-use crate::rules::{Diagnostic, Rule, RuleId, Severity};
+use crate::parser::interpolate::references;
+use crate::parser::{EnvFile, EnvVar};
+use crate::rules::{Diagnostic, Fix, Label, Rule, RuleId, Severity, Span};
 
 pub struct DuplicateKeyRule;
 
@@ -12,20 +13,50 @@ impl Rule for DuplicateKeyRule {
 
     fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
-        let mut seen_keys = HashMap::new();
+        let mut seen_keys: HashMap<&str, &EnvVar> = HashMap::new();
+        let mut previous: HashMap<&str, usize> = HashMap::new();
+
+        for (index, var) in env_file.vars.iter().enumerate() {
+            let earlier = previous.insert(&var.key, index);
+            if let (Some(first), Some(earlier)) = (seen_keys.get(var.key.as_str()), earlier) {
+                // The last definition wins, so the fix drops the one before it,
+                // unless something in between still reads that earlier value
+                let prev = &env_file.vars[earlier];
+                let read_in_between = env_file.vars[earlier + 1..=index].iter().any(|v| {
+                    references(v, env_file.dialect)
+                        .iter()
+                        .any(|r| r.name == var.key)
+                });
+                let fix = (!read_in_between)
+                    .then(|| {
+                        Fix::delete_entry(
+                            env_file,
+                            prev.line,
+                            format!("Remove the earlier definition on line {}", prev.line),
+                        )
+                    })
+                    .flatten();
 
-        for var in &env_file.vars {
-            if let Some(prev_line) = seen_keys.insert(&var.key, var.line) {
                 diagnostics.push(Diagnostic {
                     id: self.id(),
                     severity: Severity::Error,
                     message: format!(
                         "Duplicate key '{}' (first defined on line {})",
-                        var.key, prev_line
+                        var.key, first.line
                     ),
                     path: env_file.path.clone(),
                     line: Some(var.line),
+                    span: Some(Span::new(env_file, var.key_span.clone())),
+                    labels: vec![Label::new(
+                        env_file,
+                        first.key_span.clone(),
+                        "first defined here",
+                    )],
+                    constraint: None,
+                    fix,
                 });
+            } else {
+                seen_keys.insert(&var.key, var);
             }
         }
 
@@ -1,5 +1,5 @@
 use crate::parser::EnvFile;
-use crate::rules::{Diagnostic, Rule, RuleId, Severity};
+use crate::rules::{Diagnostic, Rule, RuleId, Severity, Span};
 
 pub struct EmptyValueRule;
 
@@ -19,6 +19,10 @@ impl Rule for EmptyValueRule {
                     message: format!("Key '{}' has an empty value", var.key),
                     path: env_file.path.clone(),
                     line: Some(var.line),
+                    span: Some(Span::new(env_file, var.key_span.clone())),
+                    labels: Vec::new(),
+                    constraint: None,
+                    fix: None,
                 });
             }
         }
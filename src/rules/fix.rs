@@ -0,0 +1,163 @@
+//! Machine-applicable fixes attached to diagnostics.
+
+use std::ops::Range;
+
+use crate::parser::env::TokenKind;
+use crate::parser::EnvFile;
+use crate::rules::Span;
+
+/// A single text replacement. Deleting replaces a range with nothing;
+/// inserting replaces an empty range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// 1-indexed line the edit starts on.
+    pub line: usize,
+    pub span: Span,
+    pub replacement: String,
+}
+
+impl Edit {
+    /// Replaces a byte range in `env_file` with `text`.
+    #[must_use]
+    pub fn replace(env_file: &EnvFile, range: Range<usize>, text: impl Into<String>) -> Self {
+        let (line, _) = env_file.syntax.position(range.start);
+        Self {
+            line,
+            span: Span::new(env_file, range),
+            replacement: text.into(),
+        }
+    }
+
+    /// Deletes a byte range in `env_file`.
+    #[must_use]
+    pub fn delete(env_file: &EnvFile, range: Range<usize>) -> Self {
+        Self::replace(env_file, range, "")
+    }
+
+    /// Inserts `text` at a byte offset in `env_file`.
+    #[must_use]
+    pub fn insert(env_file: &EnvFile, offset: usize, text: impl Into<String>) -> Self {
+        Self::replace(env_file, offset..offset, text)
+    }
+
+    const fn range(&self) -> Range<usize> {
+        self.span.start..self.span.end
+    }
+
+    /// Returns true if applying both edits would be ambiguous.
+    const fn conflicts(&self, other: &Self) -> bool {
+        let (a, b) = (self.range(), other.range());
+        (a.start < b.end && b.start < a.end)
+            || (a.start == b.start && (a.start == a.end || b.start == b.end))
+    }
+}
+
+/// A set of edits that resolves a diagnostic when applied together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    /// What the fix does, e.g. "Remove trailing whitespace".
+    pub message: String,
+    pub edits: Vec<Edit>,
+}
+
+impl Fix {
+    #[must_use]
+    pub fn new(message: impl Into<String>, edits: Vec<Edit>) -> Self {
+        Self {
+            message: message.into(),
+            edits,
+        }
+    }
+
+    /// Removes the line of the entry on `line`, including its line ending.
+    #[must_use]
+    pub fn delete_entry(
+        env_file: &EnvFile,
+        line: usize,
+        message: impl Into<String>,
+    ) -> Option<Self> {
+        let node = env_file.syntax.nodes().iter().find(|n| n.line == line)?;
+        Some(Self::new(
+            message,
+            vec![Edit::delete(env_file, node.span())],
+        ))
+    }
+
+    /// Removes the `export` keyword of the entry on `line`.
+    #[must_use]
+    pub fn remove_export(env_file: &EnvFile, line: usize) -> Option<Self> {
+        let node = env_file.syntax.nodes().iter().find(|n| n.line == line)?;
+        let export = node.token(TokenKind::Export)?;
+        let key = node.token(TokenKind::Key)?;
+        Some(Self::new(
+            "Remove `export`",
+            vec![Edit::delete(env_file, export.span.start..key.span.start)],
+        ))
+    }
+}
+
+/// Applies every fix whose edits do not conflict with an earlier fix.
+///
+/// Returns the fixed source and the number of fixes applied. Skipped fixes can
+/// be retried by re-running the rules on the result.
+#[must_use]
+pub fn apply<'a>(source: &str, fixes: impl IntoIterator<Item = &'a Fix>) -> (String, usize) {
+    let mut accepted: Vec<&Edit> = Vec::new();
+    let mut applied = 0;
+
+    for fix in fixes {
+        let conflict = fix.edits.iter().enumerate().any(|(i, edit)| {
+            accepted.iter().any(|other| edit.conflicts(other))
+                || fix.edits[..i].iter().any(|other| edit.conflicts(other))
+        });
+        if !conflict {
+            accepted.extend(&fix.edits);
+            applied += 1;
+        }
+    }
+
+    // Replace from the end so earlier offsets stay valid
+    accepted.sort_by_key(|edit| std::cmp::Reverse(edit.span.start));
+    let mut fixed = source.to_string();
+    for edit in accepted {
+        fixed.replace_range(edit.range(), &edit.replacement);
+    }
+
+    (fixed, applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn env_file(content: &str) -> EnvFile {
+        EnvFile::parse_content(PathBuf::from(".env"), content).unwrap()
+    }
+
+    #[test]
+    fn test_apply_skips_conflicting_fixes() {
+        let content = "A=1  \nB=2\n";
+        let file = env_file(content);
+        let trim = Fix::new("trim", vec![Edit::delete(&file, 3..5)]);
+        let overlapping = Fix::new("replace", vec![Edit::replace(&file, 2..4, "x")]);
+        let insert = Fix::new("insert", vec![Edit::insert(&file, 6, "# b\n")]);
+
+        let (fixed, applied) = apply(content, [&trim, &overlapping, &insert]);
+        assert_eq!(applied, 2);
+        assert_eq!(fixed, "A=1\n# b\nB=2\n");
+    }
+
+    #[test]
+    fn test_entry_fixes() {
+        let content = "export A=1\nB=2\n";
+        let file = env_file(content);
+
+        let (fixed, _) = apply(content, [&Fix::remove_export(&file, 1).unwrap()]);
+        assert_eq!(fixed, "A=1\nB=2\n");
+
+        let (fixed, _) = apply(content, [&Fix::delete_entry(&file, 1, "drop").unwrap()]);
+        assert_eq!(fixed, "B=2\n");
+        assert!(Fix::remove_export(&file, 2).is_none());
+    }
+}
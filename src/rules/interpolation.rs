@@ -0,0 +1,218 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::parser::interpolate::{references, Modifier, Reference};
+use crate::parser::{EnvFile, EnvVar};
+use crate::rules::{Diagnostic, Label, Rule, RuleId, Severity, Span};
+
+/// First definition of each key.
+fn definitions(env_file: &EnvFile) -> HashMap<&str, &EnvVar> {
+    let mut defined = HashMap::new();
+    for var in &env_file.vars {
+        defined.entry(var.key.as_str()).or_insert(var);
+    }
+    defined
+}
+
+/// Span of a reference inside a variable's raw value.
+fn reference_span(env_file: &EnvFile, var: &EnvVar, reference: &Reference) -> Span {
+    let start = var.raw_span.start;
+    Span::new(
+        env_file,
+        start + reference.span.start..start + reference.span.end,
+    )
+}
+
+pub struct UndefinedReferenceRule;
+
+impl Rule for UndefinedReferenceRule {
+    fn id(&self) -> RuleId {
+        RuleId::W007
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let defined = definitions(env_file);
+
+        for var in &env_file.vars {
+            for reference in references(var, env_file.dialect) {
+                // `PATH=$PATH:/bin` extends the process environment on purpose
+                if reference.name == var.key
+                    || reference.has_fallback()
+                    || defined.contains_key(reference.name.as_str())
+                {
+                    continue;
+                }
+
+                let message = if reference.modifier == Modifier::Required {
+                    format!(
+                        "Key '{}' requires '{}', which is not defined in this file",
+                        var.key, reference.name
+                    )
+                } else {
+                    format!(
+                        "Key '{}' references undefined key '{}'",
+                        var.key, reference.name
+                    )
+                };
+
+                diagnostics.push(Diagnostic {
+                    id: self.id(),
+                    severity: Severity::Warning,
+                    message,
+                    path: env_file.path.clone(),
+                    line: Some(var.line),
+                    span: Some(reference_span(env_file, var, &reference)),
+                    labels: Vec::new(),
+                    constraint: None,
+                    fix: None,
+                });
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct ForwardReferenceRule;
+
+impl Rule for ForwardReferenceRule {
+    fn id(&self) -> RuleId {
+        RuleId::W008
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let defined = definitions(env_file);
+
+        for var in &env_file.vars {
+            for reference in references(var, env_file.dialect) {
+                if reference.name == var.key {
+                    continue;
+                }
+
+                if let Some(definition) = defined.get(reference.name.as_str()) {
+                    let defined_on = definition.line;
+                    if defined_on > var.line {
+                        diagnostics.push(Diagnostic {
+                            id: self.id(),
+                            severity: Severity::Warning,
+                            message: format!(
+                                "Key '{}' references '{}' before it is defined on line {}; \
+                                 dotenv loaders resolve references top-down",
+                                var.key, reference.name, defined_on
+                            ),
+                            path: env_file.path.clone(),
+                            line: Some(var.line),
+                            span: Some(reference_span(env_file, var, &reference)),
+                            labels: vec![Label::new(
+                                env_file,
+                                definition.key_span.clone(),
+                                "defined here",
+                            )],
+                            constraint: None,
+                            fix: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        diagnostics
+    }
+}
+
+pub struct ReferenceCycleRule;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+impl ReferenceCycleRule {
+    fn visit<'a>(
+        key: &'a str,
+        graph: &HashMap<&'a str, BTreeSet<String>>,
+        state: &mut HashMap<&'a str, Visit>,
+        stack: &mut Vec<&'a str>,
+        cycles: &mut Vec<Vec<&'a str>>,
+    ) {
+        state.insert(key, Visit::InProgress);
+        stack.push(key);
+
+        for next in graph.get(key).into_iter().flatten() {
+            let Some((&next, _)) = graph.get_key_value(next.as_str()) else {
+                continue;
+            };
+            match state.get(next) {
+                Some(Visit::InProgress) => {
+                    let start = stack.iter().position(|k| *k == next).unwrap_or(0);
+                    cycles.push(stack[start..].to_vec());
+                },
+                Some(Visit::Done) => {},
+                None => Self::visit(next, graph, state, stack, cycles),
+            }
+        }
+
+        stack.pop();
+        state.insert(key, Visit::Done);
+    }
+}
+
+impl Rule for ReferenceCycleRule {
+    fn id(&self) -> RuleId {
+        RuleId::E003
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let defined = definitions(env_file);
+
+        // Edges between keys of this file; self-references resolve from the environment
+        let mut graph: HashMap<&str, BTreeSet<String>> = HashMap::new();
+        for var in &env_file.vars {
+            let edges = graph.entry(var.key.as_str()).or_default();
+            for reference in references(var, env_file.dialect) {
+                if reference.name != var.key && defined.contains_key(reference.name.as_str()) {
+                    edges.insert(reference.name);
+                }
+            }
+        }
+
+        let mut state = HashMap::new();
+        let mut cycles = Vec::new();
+        for var in &env_file.vars {
+            if !state.contains_key(var.key.as_str()) {
+                Self::visit(
+                    var.key.as_str(),
+                    &graph,
+                    &mut state,
+                    &mut Vec::new(),
+                    &mut cycles,
+                );
+            }
+        }
+
+        cycles
+            .into_iter()
+            .map(|cycle| {
+                let first = cycle[0];
+                let mut chain = cycle.join(" -> ");
+                chain.push_str(" -> ");
+                chain.push_str(first);
+
+                let definition = defined.get(first);
+                Diagnostic {
+                    id: self.id(),
+                    severity: Severity::Error,
+                    message: format!("Reference cycle: {chain}"),
+                    path: env_file.path.clone(),
+                    line: definition.map(|var| var.line),
+                    span: definition.map(|var| Span::new(env_file, var.key_span.clone())),
+                    labels: Vec::new(),
+                    constraint: None,
+                    fix: None,
+                }
+            })
+            .collect()
+    }
+}
@@ -1,29 +1,69 @@
+use crate::config::Config;
 use crate::parser::EnvFile;
+use crate::schema::Violation;
+
+pub use fix::{Edit, Fix};
 use std::fmt;
+use std::ops::Range;
 use std::path::PathBuf;
 
+pub mod custom;
+pub mod dialect;
 pub mod duplicate;
 pub mod empty;
+pub mod fix;
+pub mod interpolation;
+pub mod naming;
+pub mod placeholder;
+pub mod schema;
+pub mod secrets;
+pub mod shell;
 pub mod sort;
+pub mod suppression;
 pub mod syntax;
+pub mod values;
 pub mod whitespace;
 
 /// Unique identifier for a lint rule.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum RuleId {
     E001, // Duplicate key
     E002, // Invalid syntax
+    E003, // Reference cycle
+    E004, // Secret in a template file
+    E005, // Value violates the schema
+    E006, // Required key missing
+    E007, // Placeholder value in a production file
+    E008, // Secret value shared across environments
     W001, // Empty value
     W002, // Trailing whitespace
     W003, // Unsorted keys (future)
     W004, // Missing key in comparison
     W005, // K8s Secret missing in .env
     W006, // .env Key not used in K8s
+    W007, // Reference to undefined key
+    W008, // Forward reference
+    W009, // Dialect reads the line differently
+    W010, // Unused suppression comment
+    W011, // Value looks like a secret
+    W012, // Key not declared in the schema
+    W013, // Value changes meaning when sourced by a shell
+    W014, // Key breaks the naming convention
+    W015, // Key lacks an allowed prefix
+    W016, // Keys differ only in case
+    W017, // Key missing from the compare reference
+    W018, // Value differs across environments but must be equal
+    W019, // Value differs across environments
+    /// A rule declared under `rules.custom` in `.envcheckrc`
+    Custom(String),
 }
 
 impl fmt::Display for RuleId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{self:?}")
+        match self {
+            Self::Custom(id) => f.write_str(id),
+            _ => write!(f, "{self:?}"),
+        }
     }
 }
 
@@ -45,6 +85,72 @@ impl fmt::Display for Severity {
     }
 }
 
+impl std::str::FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warning" | "warn" => Ok(Self::Warning),
+            "info" | "note" => Ok(Self::Info),
+            _ => Err(format!("Unknown severity: {s}")),
+        }
+    }
+}
+
+/// The exact source range a diagnostic points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset where the range starts.
+    pub start: usize,
+    /// Byte offset just past the end of the range.
+    pub end: usize,
+    /// 1-indexed character column of the first character.
+    pub column: usize,
+    /// 1-indexed line the range ends on.
+    pub end_line: usize,
+    /// 1-indexed character column just past the last character.
+    pub end_column: usize,
+}
+
+impl Span {
+    /// Creates the span of a byte range in `env_file`.
+    #[must_use]
+    pub fn new(env_file: &EnvFile, range: Range<usize>) -> Self {
+        let (_, column) = env_file.syntax.position(range.start);
+        let (end_line, end_column) = env_file.syntax.position(range.end);
+        Self {
+            start: range.start,
+            end: range.end,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+/// A secondary location that explains a diagnostic, like "first defined here".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    /// 1-indexed line of the label.
+    pub line: usize,
+    pub span: Span,
+    pub message: String,
+}
+
+impl Label {
+    /// Creates a label for a byte range in `env_file`.
+    #[must_use]
+    pub fn new(env_file: &EnvFile, range: Range<usize>, message: impl Into<String>) -> Self {
+        let (line, _) = env_file.syntax.position(range.start);
+        Self {
+            line,
+            span: Span::new(env_file, range),
+            message: message.into(),
+        }
+    }
+}
+
 /// A diagnostic message produced by a rule.
 #[derive(Debug, Clone)]
 pub struct Diagnostic {
@@ -53,6 +159,14 @@ pub struct Diagnostic {
     pub message: String,
     pub path: PathBuf,
     pub line: Option<usize>,
+    /// Exact range within the file, when the rule can point at one.
+    pub span: Option<Span>,
+    /// Related locations shown alongside the source snippet.
+    pub labels: Vec<Label>,
+    /// The schema constraint a value failed, for schema rules.
+    pub constraint: Option<Violation>,
+    /// Edits that resolve the diagnostic, applied by `envcheck fix`.
+    pub fix: Option<Fix>,
 }
 
 /// Trait implemented by all lint rules.
@@ -64,21 +178,59 @@ pub trait Rule {
     fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic>;
 }
 
-/// Runs all registered lint rules on a file.
+/// Runs all registered lint rules on a file with the default configuration.
 #[must_use]
 pub fn check_file(env_file: &EnvFile) -> Vec<Diagnostic> {
-    let rules: Vec<Box<dyn Rule>> = vec![
+    check_file_with_config(env_file, &Config::default())
+}
+
+/// Runs all registered lint rules on a file, using the rule settings in `config`,
+/// followed by the custom rules that apply to the file.
+#[must_use]
+pub fn check_file_with_config(env_file: &EnvFile, config: &Config) -> Vec<Diagnostic> {
+    let secret_rule = secrets::SecretRule::new(&config.secret_patterns, &env_file.path);
+    let mut diagnostics = secret_rule.check(env_file);
+
+    let mut rules: Vec<Box<dyn Rule>> = vec![
         Box::new(duplicate::DuplicateKeyRule),
         Box::new(syntax::SyntaxRule),
         Box::new(empty::EmptyValueRule),
         Box::new(whitespace::TrailingWhitespaceRule),
-        Box::new(sort::UnsortedKeysRule),
+        Box::new(sort::UnsortedKeysRule::new(sort::KeyOrder::new(
+            &config.sort,
+        ))),
+        Box::new(interpolation::UndefinedReferenceRule),
+        Box::new(interpolation::ForwardReferenceRule),
+        Box::new(interpolation::ReferenceCycleRule),
+        Box::new(dialect::DialectMismatchRule),
+        Box::new(shell::ShellSourceRule),
+        Box::new(naming::NamingRule::new(
+            &config.naming,
+            config.prefixes_for(&env_file.path),
+        )),
     ];
+    // Placeholders are expected in templates, even ones named like production files
+    if config.is_production(&env_file.path) && !secrets::is_template(&env_file.path) {
+        rules.push(Box::new(placeholder::PlaceholderRule::new(
+            &config.placeholder_patterns,
+        )));
+    }
+    if let Some(env_schema) = &config.env_schema {
+        rules.push(Box::new(schema::SchemaRule::new(env_schema)));
+    }
+    for custom in &config.custom_rules {
+        if custom.files.is_empty() || config.matches(&env_file.path, &custom.files) {
+            rules.push(Box::new(custom.clone()));
+        }
+    }
 
-    let mut diagnostics = Vec::new();
     for rule in rules {
         diagnostics.extend(rule.check(env_file));
     }
+    secret_rule.hide_secrets(env_file, &mut diagnostics);
+
+    // Drop findings silenced by `# envcheck-disable` comments
+    let mut diagnostics = suppression::apply(env_file, diagnostics);
 
     // Sort diagnostics by line number for better readability
     diagnostics.sort_by_key(|d| d.line.unwrap_or(0));
@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::config::NamingConfig;
+use crate::parser::{EnvFile, EnvVar};
+use crate::rules::{Diagnostic, Label, Rule, RuleId, Severity, Span};
+
+/// Naming convention for keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NamingStyle {
+    /// `DATABASE_URL`
+    #[default]
+    ScreamingSnake,
+    /// `database_url`
+    Snake,
+    /// No style; only dots, dashes and prefixes are checked.
+    Any,
+}
+
+impl NamingStyle {
+    const fn pattern(self) -> Option<&'static str> {
+        match self {
+            Self::ScreamingSnake => Some("^[A-Z][A-Z0-9]*(_[A-Z0-9]+)*$"),
+            Self::Snake => Some("^[a-z][a-z0-9]*(_[a-z0-9]+)*$"),
+            Self::Any => None,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+            Self::Snake => "snake_case",
+            Self::Any => "any",
+        }
+    }
+}
+
+/// Enforces the key naming convention, required prefixes and consistent casing.
+pub struct NamingRule<'a> {
+    style: Option<(Regex, String)>,
+    prefixes: &'a [String],
+}
+
+impl<'a> NamingRule<'a> {
+    /// Builds the rule from `.envcheckrc` settings; `pattern` takes precedence over
+    /// `style` and was checked by [`Config::compile_rules`](crate::config::Config::compile_rules).
+    #[must_use]
+    pub fn new(config: &NamingConfig, prefixes: &'a [String]) -> Self {
+        let style = match &config.pattern {
+            Some(p) => Regex::new(p).ok().map(|re| (re, format!("`{p}`"))),
+            None => config.style.pattern().and_then(|pattern| {
+                Some((Regex::new(pattern).ok()?, config.style.name().to_string()))
+            }),
+        };
+
+        Self { style, prefixes }
+    }
+
+    /// Describes what is wrong with the spelling of `key`, if anything.
+    fn problem(&self, key: &str) -> Option<String> {
+        // Whitespace and leading digits are syntax errors (E002)
+        if key.contains(char::is_whitespace) || key.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        if let Some(c) = key
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && *c != '_')
+        {
+            return Some(format!(
+                "Key '{key}' contains `{c}`, so a POSIX shell cannot export it"
+            ));
+        }
+
+        let (re, name) = self.style.as_ref()?;
+        (!re.is_match(key)).then(|| format!("Key '{key}' is not {name}"))
+    }
+}
+
+/// A warning pointing at the key of `var`.
+fn warning(env_file: &EnvFile, id: RuleId, var: &EnvVar, message: String) -> Diagnostic {
+    Diagnostic {
+        id,
+        severity: Severity::Warning,
+        message,
+        path: env_file.path.clone(),
+        line: Some(var.line),
+        span: Some(Span::new(env_file, var.key_span.clone())),
+        labels: Vec::new(),
+        constraint: None,
+        fix: None,
+    }
+}
+
+impl Rule for NamingRule<'_> {
+    fn id(&self) -> RuleId {
+        RuleId::W014
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut seen: HashMap<String, &EnvVar> = HashMap::new();
+
+        for var in &env_file.vars {
+            if let Some(message) = self.problem(&var.key) {
+                diagnostics.push(warning(env_file, self.id(), var, message));
+            }
+
+            if !self.prefixes.is_empty()
+                && !self
+                    .prefixes
+                    .iter()
+                    .any(|p| var.key.starts_with(p.as_str()))
+            {
+                let message = format!(
+                    "Key '{}' does not start with an allowed prefix ({})",
+                    var.key,
+                    self.prefixes.join(", ")
+                );
+                diagnostics.push(warning(env_file, RuleId::W015, var, message));
+            }
+
+            // Exact duplicates are E001
+            let first = *seen.entry(var.key.to_uppercase()).or_insert(var);
+            if first.key != var.key {
+                let message = format!(
+                    "Key '{}' differs only in case from '{}' (line {})",
+                    var.key, first.key, first.line
+                );
+                let mut diagnostic = warning(env_file, RuleId::W016, var, message);
+                diagnostic.labels.push(Label::new(
+                    env_file,
+                    first.key_span.clone(),
+                    "first spelled here",
+                ));
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        diagnostics
+    }
+}
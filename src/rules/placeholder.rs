@@ -0,0 +1,125 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::config::ProductionConfig;
+use crate::error::{EnvCheckError, Result};
+use crate::parser::interpolate::Interpolator;
+use crate::parser::EnvFile;
+use crate::rules::{Diagnostic, Rule, RuleId, Severity};
+
+fn case_insensitive(setting: &str, pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| EnvCheckError::invalid_config(setting, e))
+}
+
+/// The compiled `production` patterns, shared by the [`PlaceholderRule`] of every file.
+#[derive(Debug, Clone)]
+pub struct PlaceholderPatterns {
+    placeholders: Vec<Regex>,
+    unsafe_values: Vec<(glob::Pattern, Regex)>,
+}
+
+impl PlaceholderPatterns {
+    /// Compiles the placeholder and unsafe value patterns. Fails on an invalid
+    /// regex or glob.
+    pub fn new(config: &ProductionConfig) -> Result<Self> {
+        Ok(Self {
+            placeholders: config
+                .placeholders
+                .iter()
+                .map(|p| case_insensitive("production.placeholders", p))
+                .collect::<Result<_>>()?,
+            unsafe_values: config
+                .unsafe_values
+                .iter()
+                .map(|(key, value)| {
+                    let glob = glob::Pattern::new(key).map_err(|e| {
+                        EnvCheckError::invalid_config("production.unsafe_values", e)
+                    })?;
+                    Ok((
+                        glob,
+                        case_insensitive(&format!("production.unsafe_values.{key}"), value)?,
+                    ))
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+impl Default for PlaceholderPatterns {
+    fn default() -> Self {
+        Self::new(&ProductionConfig::default()).expect("default production settings compile")
+    }
+}
+
+/// Flags placeholder and unsafe default values in production files.
+pub struct PlaceholderRule<'a> {
+    patterns: &'a PlaceholderPatterns,
+}
+
+impl<'a> PlaceholderRule<'a> {
+    #[must_use]
+    pub const fn new(patterns: &'a PlaceholderPatterns) -> Self {
+        Self { patterns }
+    }
+}
+
+impl Rule for PlaceholderRule<'_> {
+    fn id(&self) -> RuleId {
+        RuleId::E007
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let values = Interpolator::new().resolve_vars(env_file);
+        let mut diagnostics = Vec::new();
+
+        for (var, value) in env_file.vars.iter().zip(&values) {
+            if value.is_empty() {
+                continue;
+            }
+
+            // Only the matched text is shown, and no snippet: the rest of the value
+            // may be a real secret
+            let message = if let Some(found) = self
+                .patterns
+                .placeholders
+                .iter()
+                .find_map(|re| re.find(value))
+            {
+                format!(
+                    "Key '{}' contains placeholder `{}` in a production file",
+                    var.key,
+                    found.as_str()
+                )
+            } else if let Some((_, unsafe_value)) = self
+                .patterns
+                .unsafe_values
+                .iter()
+                .find(|(key, re)| key.matches(&var.key) && re.is_match(value))
+            {
+                format!(
+                    "Key '{}' matches unsafe value `{}` in a production file",
+                    var.key,
+                    unsafe_value.as_str()
+                )
+            } else {
+                continue;
+            };
+
+            diagnostics.push(Diagnostic {
+                id: self.id(),
+                severity: Severity::Error,
+                message,
+                path: env_file.path.clone(),
+                line: Some(var.line),
+                span: None,
+                labels: Vec::new(),
+                constraint: None,
+                fix: None,
+            });
+        }
+
+        diagnostics
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashSet;
+
+use crate::parser::interpolate::Interpolator;
+use crate::parser::EnvFile;
+use crate::rules::{Diagnostic, Rule, RuleId, Severity, Span};
+use crate::schema::EnvSchema;
+
+/// Validates keys and values against an `envcheck.schema.toml` contract.
+pub struct SchemaRule<'a> {
+    schema: &'a EnvSchema,
+}
+
+impl<'a> SchemaRule<'a> {
+    #[must_use]
+    pub const fn new(schema: &'a EnvSchema) -> Self {
+        Self { schema }
+    }
+}
+
+impl Rule for SchemaRule<'_> {
+    fn id(&self) -> RuleId {
+        RuleId::E005
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let values = Interpolator::new().resolve_vars(env_file);
+
+        for (var, value) in env_file.vars.iter().zip(&values) {
+            let Some(contract) = self.schema.keys.get(&var.key) else {
+                diagnostics.push(Diagnostic {
+                    id: RuleId::W012,
+                    severity: Severity::Warning,
+                    message: format!("Key '{}' is not declared in the schema", var.key),
+                    path: env_file.path.clone(),
+                    line: Some(var.line),
+                    span: Some(Span::new(env_file, var.key_span.clone())),
+                    labels: Vec::new(),
+                    constraint: None,
+                    fix: None,
+                });
+                continue;
+            };
+
+            // Empty values are reported by W001
+            if value.is_empty() {
+                continue;
+            }
+
+            if let Err(violation) = contract.validate(&var.key, value) {
+                diagnostics.push(Diagnostic {
+                    id: self.id(),
+                    severity: Severity::Error,
+                    message: format!(
+                        "Key '{}' must be {} ({})",
+                        var.key, violation.expected, violation.value_type
+                    ),
+                    path: env_file.path.clone(),
+                    line: Some(var.line),
+                    span: Some(Span::new(env_file, var.raw_span.clone())),
+                    labels: Vec::new(),
+                    constraint: Some(violation),
+                    fix: None,
+                });
+            }
+        }
+
+        let defined: HashSet<&str> = env_file.vars.iter().map(|v| v.key.as_str()).collect();
+        for (key, contract) in &self.schema.keys {
+            if contract.required && !defined.contains(key.as_str()) {
+                diagnostics.push(Diagnostic {
+                    id: RuleId::E006,
+                    severity: Severity::Error,
+                    message: format!("Required key '{key}' is missing"),
+                    path: env_file.path.clone(),
+                    line: None,
+                    span: None,
+                    labels: Vec::new(),
+                    constraint: Some(contract.violation(key, "required")),
+                    fix: None,
+                });
+            }
+        }
+
+        diagnostics
+    }
+}
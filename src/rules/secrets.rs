@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::config::SecretsConfig;
+use crate::error::{EnvCheckError, Result};
+use crate::parser::{EnvFile, EnvVar};
+use crate::rules::{Diagnostic, Rule, RuleId, Severity};
+
+/// Well-known credential formats, by description.
+const DETECTORS: [(&str, &str); 9] = [
+    (
+        "an AWS access key ID",
+        r"\b(?:AKIA|ASIA|ABIA|ACCA)[0-9A-Z]{16}\b",
+    ),
+    ("a GitHub token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+    ("a GitHub token", r"\bgithub_pat_[A-Za-z0-9_]{22,}\b"),
+    ("a GitLab token", r"\bglpat-[A-Za-z0-9_\-]{20,}"),
+    ("a Slack token", r"\bxox[abposr]-[A-Za-z0-9\-]{10,}"),
+    (
+        "a Slack webhook URL",
+        r"https://hooks\.slack\.com/services/T[A-Z0-9]+/B[A-Z0-9]+/[A-Za-z0-9]+",
+    ),
+    (
+        "a private key",
+        r"-----BEGIN (?:[A-Z0-9]+ )*PRIVATE KEY-----",
+    ),
+    (
+        "a JSON Web Token",
+        r"\beyJ[A-Za-z0-9_\-]{10,}\.eyJ[A-Za-z0-9_\-]{10,}\.[A-Za-z0-9_\-]{10,}",
+    ),
+    (
+        "a Stripe key",
+        r"\b(?:sk|rk)_(?:live|test)_[A-Za-z0-9]{16,}\b",
+    ),
+];
+
+fn detectors() -> &'static [(&'static str, Regex)] {
+    static COMPILED: OnceLock<Vec<(&str, Regex)>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        DETECTORS
+            .iter()
+            .map(|(kind, pattern)| (*kind, Regex::new(pattern).expect("valid detector regex")))
+            .collect()
+    })
+}
+
+/// Shannon entropy of `value` in bits per character.
+#[allow(clippy::cast_precision_loss)]
+fn entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+
+    let len = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Returns true for files that are committed as templates, like `.env.example`.
+pub(crate) fn is_template(path: &Path) -> bool {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .is_some_and(|name| {
+            ["example", "sample", "template"]
+                .iter()
+                .any(|t| name.contains(t))
+        })
+}
+
+/// The compiled `secrets` settings, shared by the [`SecretRule`] of every file.
+#[derive(Debug, Clone)]
+pub struct SecretPatterns {
+    allow: Vec<Regex>,
+    allow_keys: Vec<glob::Pattern>,
+    entropy_threshold: f64,
+    min_length: usize,
+}
+
+impl SecretPatterns {
+    /// Compiles the allowlists. Fails on an invalid regex or glob.
+    pub fn new(config: &SecretsConfig) -> Result<Self> {
+        Ok(Self {
+            allow: config
+                .allow
+                .iter()
+                .map(|p| {
+                    Regex::new(p).map_err(|e| EnvCheckError::invalid_config("secrets.allow", e))
+                })
+                .collect::<Result<_>>()?,
+            allow_keys: config
+                .allow_keys
+                .iter()
+                .map(|p| {
+                    glob::Pattern::new(p)
+                        .map_err(|e| EnvCheckError::invalid_config("secrets.allow_keys", e))
+                })
+                .collect::<Result<_>>()?,
+            entropy_threshold: config.entropy_threshold,
+            min_length: config.min_length,
+        })
+    }
+}
+
+impl Default for SecretPatterns {
+    fn default() -> Self {
+        Self::new(&SecretsConfig::default()).expect("default secrets settings compile")
+    }
+}
+
+/// Flags values that look like real credentials: errors in template files,
+/// warnings elsewhere.
+pub struct SecretRule<'a> {
+    /// Whether the rule checks a template file (E004) or a real one (W011).
+    template: bool,
+    patterns: &'a SecretPatterns,
+}
+
+impl<'a> SecretRule<'a> {
+    /// Builds the rule for the file at `path`.
+    #[must_use]
+    pub fn new(patterns: &'a SecretPatterns, path: &Path) -> Self {
+        Self {
+            template: is_template(path),
+            patterns,
+        }
+    }
+
+    /// Returns the variables whose values look like secrets, with the kind of each.
+    fn flagged<'f>(
+        &'f self,
+        env_file: &'f EnvFile,
+    ) -> impl Iterator<Item = (&'f EnvVar, &'static str)> + 'f {
+        env_file.vars.iter().filter_map(move |var| {
+            let allowed = self.patterns.allow_keys.iter().any(|p| p.matches(&var.key))
+                || self.patterns.allow.iter().any(|re| re.is_match(&var.value));
+            if allowed {
+                return None;
+            }
+            self.detect(&var.value).map(|kind| (var, kind))
+        })
+    }
+
+    /// Drops the snippets and labels other rules point at lines holding a secret,
+    /// since printing the source line would copy the secret into CI logs.
+    pub(crate) fn hide_secrets(&self, env_file: &EnvFile, diagnostics: &mut [Diagnostic]) {
+        let lines: Vec<_> = self
+            .flagged(env_file)
+            .map(|(var, _)| var.line..=var.end_line)
+            .collect();
+        let shows_secret = |first: usize, last: usize| {
+            lines
+                .iter()
+                .any(|l| first <= *l.end() && *l.start() <= last)
+        };
+
+        for diagnostic in diagnostics {
+            if let (Some(line), Some(span)) = (diagnostic.line, &diagnostic.span) {
+                if shows_secret(line, span.end_line) {
+                    diagnostic.span = None;
+                }
+            }
+            diagnostic
+                .labels
+                .retain(|label| !shows_secret(label.line, label.span.end_line));
+        }
+    }
+
+    /// Describes the kind of secret `value` looks like, if any.
+    fn detect(&self, value: &str) -> Option<&'static str> {
+        if let Some((kind, _)) = detectors().iter().find(|(_, re)| re.is_match(value)) {
+            return Some(kind);
+        }
+
+        // Random-looking tokens: long, no spaces or references, high entropy
+        let token_like = value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+/=_-.".contains(c));
+        (token_like
+            && value.len() >= self.patterns.min_length
+            && entropy(value) >= self.patterns.entropy_threshold)
+            .then_some("a high-entropy secret")
+    }
+}
+
+impl Rule for SecretRule<'_> {
+    fn id(&self) -> RuleId {
+        if self.template {
+            RuleId::E004
+        } else {
+            RuleId::W011
+        }
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for (var, kind) in self.flagged(env_file) {
+            let (severity, message) = if self.template {
+                (
+                    Severity::Error,
+                    format!(
+                        "Key '{}' contains what looks like {kind}; template files must not contain real secrets",
+                        var.key
+                    ),
+                )
+            } else {
+                (
+                    Severity::Warning,
+                    format!("Key '{}' contains what looks like {kind}", var.key),
+                )
+            };
+
+            // No span: a source snippet would copy the secret into CI logs
+            diagnostics.push(Diagnostic {
+                id: self.id(),
+                severity,
+                message,
+                path: env_file.path.clone(),
+                line: Some(var.line),
+                span: None,
+                labels: Vec::new(),
+                constraint: None,
+                fix: None,
+            });
+        }
+
+        diagnostics
+    }
+}
@@ -0,0 +1,180 @@
+//! Compatibility with `set -a; . ./.env`.
+//!
+//! POSIX shells do not word-split or glob assignment values, but they still end
+//! the assignment at unquoted whitespace or operators, run command
+//! substitutions, expand `$NAME` and `~`, and remove quotes and backslashes.
+
+use std::path::PathBuf;
+
+use crate::parser::dialect::Interpolation;
+use crate::parser::interpolate::references;
+use crate::parser::{Dialect, EnvFile, EnvVar, QuoteStyle};
+use crate::rules::{Diagnostic, Edit, Fix, Rule, RuleId, Severity, Span};
+
+/// Reports values whose meaning changes when the file is sourced by a shell.
+pub struct ShellSourceRule;
+
+/// Returns true if `key` is a valid shell variable name.
+fn is_shell_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Explains how the shell reads a `$` at the start of `rest`, if differently from `dialect`.
+fn dollar_hazard(rest: &str, dialect: Dialect) -> Option<String> {
+    let next = rest[1..].chars().next()?;
+    if next == '(' {
+        return Some("the shell executes the `$(...)` command substitution".to_string());
+    }
+
+    let braced = next == '{';
+    let name: String = if braced {
+        rest[2..].chars().take_while(|&c| c != '}').collect()
+    } else if next.is_ascii_digit() || "?$!#@*-".contains(next) {
+        next.to_string()
+    } else {
+        rest[1..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect()
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    let expanded = match dialect.features().interpolation {
+        Interpolation::None => false,
+        Interpolation::BracesOnly => braced,
+        Interpolation::Full => is_shell_name(&name),
+    };
+    (!expanded).then(|| {
+        format!(
+            "the shell expands a `$` reference, but {} keeps it literally",
+            dialect.name()
+        )
+    })
+}
+
+/// Explains how sourcing `var` with a POSIX shell differs from parsing it with `dialect`.
+#[must_use]
+pub fn hazard(var: &EnvVar, dialect: Dialect) -> Option<String> {
+    // Keys the shell cannot assign are reported by the naming rule (W014)
+    if !is_shell_name(&var.key) {
+        return None;
+    }
+
+    let raw = var.raw_value.as_str();
+    match var.quote {
+        QuoteStyle::Single => None,
+        QuoteStyle::Backtick => {
+            Some("the shell runs the backtick-quoted value as a command".to_string())
+        },
+        QuoteStyle::Double => raw.char_indices().find_map(|(i, c)| match c {
+            '`' => Some("the shell executes the text between backticks".to_string()),
+            '$' => dollar_hazard(&raw[i..], dialect),
+            _ => None,
+        }),
+        QuoteStyle::None => raw.char_indices().find_map(|(i, c)| match c {
+            c if c.is_whitespace() => Some(
+                "the shell stops the value at the space and runs the rest as a command".to_string(),
+            ),
+            ';' | '&' | '|' => Some(format!(
+                "`{c}` ends the assignment, so the shell runs the rest of the line as a command"
+            )),
+            '<' | '>' => Some(format!("`{c}` makes the shell redirect to or from a file")),
+            '(' | ')' => Some(format!("`{c}` is a shell syntax error")),
+            '`' => Some("the shell executes the text between backticks".to_string()),
+            '$' => dollar_hazard(&raw[i..], dialect),
+            '\\' => Some("the shell removes the backslash".to_string()),
+            '\'' | '"' => Some(format!(
+                "the shell treats `{c}` as the start of a quoted string"
+            )),
+            '~' if i == 0 || raw[..i].ends_with(':') => {
+                Some("the shell expands `~` to the home directory".to_string())
+            },
+            '*' | '?' | '[' if var.exported => Some(format!(
+                "some shells expand `{c}` in `export` arguments as a file glob"
+            )),
+            _ => None,
+        }),
+    }
+}
+
+/// Quotes the value of `var` so that the shell and `dialect` read it the same way.
+///
+/// Returns `None` when no quoting keeps both readings identical.
+#[must_use]
+pub fn quote(var: &EnvVar, dialect: Dialect) -> Option<String> {
+    let value = &var.value;
+    if !dialect.features().quotes || !is_shell_name(&var.key) || var.is_multiline() {
+        return None;
+    }
+
+    // Single quotes are literal for both, unless the dialect expands references
+    if !value.contains('\'') && references(var, dialect).is_empty() {
+        return Some(format!("'{value}'"));
+    }
+
+    // Double quotes only leave `$` expansion, which must then mean the same to both
+    let special = value.contains(['"', '\\', '`']) || value.contains("$(");
+    let quoted = format!("\"{value}\"");
+    let candidate = EnvFile::parse_content_with_dialect(
+        PathBuf::new(),
+        &format!("{}={quoted}\n", var.key),
+        dialect,
+    )
+    .ok()?;
+    let same_for_dialect = candidate.vars.first().is_some_and(|v| {
+        v.value == *value && references(v, dialect).len() == references(var, dialect).len()
+    });
+    let same_for_shell = candidate
+        .vars
+        .first()
+        .is_some_and(|v| hazard(v, dialect).is_none());
+
+    (!special && same_for_dialect && same_for_shell).then_some(quoted)
+}
+
+impl Rule for ShellSourceRule {
+    fn id(&self) -> RuleId {
+        RuleId::W013
+    }
+
+    fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if !env_file.dialect.shell_sourced() {
+            return diagnostics;
+        }
+
+        for var in &env_file.vars {
+            let Some(reason) = hazard(var, env_file.dialect) else {
+                continue;
+            };
+
+            diagnostics.push(Diagnostic {
+                id: self.id(),
+                severity: Severity::Warning,
+                message: format!(
+                    "Sourcing '{}' in a shell changes its meaning: {reason}",
+                    var.key
+                ),
+                path: env_file.path.clone(),
+                line: Some(var.line),
+                span: Some(Span::new(env_file, var.raw_span.clone())),
+                labels: Vec::new(),
+                constraint: None,
+                fix: quote(var, env_file.dialect).map(|quoted| {
+                    Fix::new(
+                        "Quote the value",
+                        vec![Edit::replace(env_file, var.raw_span.clone(), quoted)],
+                    )
+                }),
+            });
+        }
+
+        diagnostics
+    }
+}
@@ -1,35 +1,196 @@
+use std::cmp::Ordering;
+
+use serde::Deserialize;
+
+use crate::config::SortConfig;
+use crate::parser::section::sections;
 use crate::parser::EnvFile;
-use crate::rules::{Diagnostic, Rule, RuleId, Severity};
+use crate::rules::{Diagnostic, Rule, RuleId, Severity, Span};
+
+/// How keys are ordered within a section.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortOrder {
+    /// Byte order: `A_KEY`, `B_KEY`, `a_key`
+    #[default]
+    Alphabetical,
+    /// Alphabetical, ignoring ASCII case
+    CaseInsensitive,
+    /// Numbers compare by value: `KEY_2` before `KEY_10`
+    Natural,
+    /// Grouped by the text before the first `_`, then alphabetical
+    Prefix,
+    /// The order of a reference file; other keys follow alphabetically
+    Reference,
+}
+
+/// Compares keys according to the configured [`SortOrder`].
+#[derive(Debug, Clone, Copy)]
+pub struct KeyOrder<'a> {
+    order: SortOrder,
+    reference: &'a [String],
+}
+
+impl<'a> KeyOrder<'a> {
+    #[must_use]
+    pub fn new(config: &'a SortConfig) -> Self {
+        Self {
+            order: config.order,
+            reference: &config.reference_keys,
+        }
+    }
 
-pub struct UnsortedKeysRule;
+    /// Orders two keys. Keys that compare equal keep their relative position.
+    #[must_use]
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self.order {
+            SortOrder::Alphabetical => a.cmp(b),
+            SortOrder::CaseInsensitive => a
+                .bytes()
+                .map(|c| c.to_ascii_lowercase())
+                .cmp(b.bytes().map(|c| c.to_ascii_lowercase())),
+            SortOrder::Natural => natural(a, b),
+            SortOrder::Prefix => (prefix(a), a).cmp(&(prefix(b), b)),
+            SortOrder::Reference => {
+                let position = |key: &str| self.reference.iter().position(|r| r == key);
+                match (position(a), position(b)) {
+                    (Some(x), Some(y)) => x.cmp(&y),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => a.cmp(b),
+                }
+            },
+        }
+    }
+}
 
-impl Rule for UnsortedKeysRule {
+fn prefix(key: &str) -> &str {
+    key.split_once('_').map_or(key, |(prefix, _)| prefix)
+}
+
+/// Compares runs of digits by value and everything else byte by byte.
+fn natural(mut a: &str, mut b: &str) -> Ordering {
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (da, db) = (digits(a), digits(b));
+                let na = a[..da].trim_start_matches('0');
+                let nb = b[..db].trim_start_matches('0');
+                let ordering = na.len().cmp(&nb.len()).then_with(|| na.cmp(nb));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                (a, b) = (&a[da..], &b[db..]);
+            },
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+            },
+        }
+    }
+}
+
+/// Warns about keys out of order within their section.
+pub struct UnsortedKeysRule<'a> {
+    order: KeyOrder<'a>,
+}
+
+impl<'a> UnsortedKeysRule<'a> {
+    #[must_use]
+    pub const fn new(order: KeyOrder<'a>) -> Self {
+        Self { order }
+    }
+}
+
+impl Rule for UnsortedKeysRule<'_> {
     fn id(&self) -> RuleId {
         RuleId::W003
     }
 
     fn check(&self, env_file: &EnvFile) -> Vec<Diagnostic> {
+        let tree = &env_file.syntax;
         let mut diagnostics = Vec::new();
-        let mut last_key: Option<&str> = None;
-
-        for var in &env_file.vars {
-            if let Some(prev) = last_key {
-                if var.key.as_str() < prev {
-                    diagnostics.push(Diagnostic {
-                        id: self.id(),
-                        severity: Severity::Warning,
-                        message: format!(
-                            "Unsorted key '{}' should come before '{}'",
-                            var.key, prev
-                        ),
-                        path: env_file.path.clone(),
-                        line: Some(var.line),
-                    });
+
+        // Keys are only compared with their neighbours in the same section
+        for section in sections(tree) {
+            let mut last_key: Option<&str> = None;
+
+            for entry in &section.entries {
+                let node = &tree.nodes()[entry.node];
+                let Some(var) = env_file.vars.iter().find(|v| v.line == node.line) else {
+                    continue;
+                };
+                if let Some(prev) = last_key {
+                    if self.order.compare(&var.key, prev) == Ordering::Less {
+                        diagnostics.push(Diagnostic {
+                            id: self.id(),
+                            severity: Severity::Warning,
+                            message: format!(
+                                "Unsorted key '{}' should come before '{}'",
+                                var.key, prev
+                            ),
+                            path: env_file.path.clone(),
+                            line: Some(var.line),
+                            span: Some(Span::new(env_file, var.key_span.clone())),
+                            labels: Vec::new(),
+                            constraint: None,
+                            fix: None,
+                        });
+                    }
                 }
+                last_key = Some(&var.key);
             }
-            last_key = Some(&var.key);
         }
 
         diagnostics
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(order: SortOrder, reference: &[&str], keys: &[&'static str]) -> Vec<&'static str> {
+        let config = SortConfig {
+            order,
+            reference_keys: reference.iter().map(ToString::to_string).collect(),
+            ..SortConfig::default()
+        };
+        let order = KeyOrder::new(&config);
+        let mut keys = keys.to_vec();
+        keys.sort_by(|a, b| order.compare(a, b));
+        keys
+    }
+
+    #[test]
+    fn test_sort_orders() {
+        let keys = ["KEY_10", "db_b", "DBA", "KEY_2", "DB_A"];
+        assert_eq!(
+            sorted(SortOrder::Alphabetical, &[], &keys),
+            ["DBA", "DB_A", "KEY_10", "KEY_2", "db_b"]
+        );
+        assert_eq!(
+            sorted(SortOrder::CaseInsensitive, &[], &keys),
+            ["DB_A", "db_b", "DBA", "KEY_10", "KEY_2"]
+        );
+        assert_eq!(
+            sorted(SortOrder::Natural, &[], &keys),
+            ["DBA", "DB_A", "KEY_2", "KEY_10", "db_b"]
+        );
+        assert_eq!(
+            sorted(SortOrder::Prefix, &[], &keys),
+            ["DB_A", "DBA", "KEY_10", "KEY_2", "db_b"]
+        );
+        assert_eq!(
+            sorted(SortOrder::Reference, &["KEY_2", "DB_A"], &keys),
+            ["KEY_2", "DB_A", "DBA", "KEY_10", "db_b"]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Inline suppression comments.
+//!
+//! - `# envcheck-disable-next-line W001` silences the next line
+//! - `KEY= # envcheck-disable-line W001` silences the line it is on
+//! - `# envcheck-disable W003` silences the whole file
+//!
+//! Rule IDs may be separated by commas or spaces; without any, every rule is
+//! silenced. Text after `--` is a free-form reason.
+
+use std::ops::{Range, RangeInclusive};
+
+use crate::parser::env::TokenKind;
+use crate::parser::EnvFile;
+use crate::rules::{Diagnostic, RuleId, Severity, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scope {
+    File,
+    Line,
+    NextLine,
+}
+
+#[derive(Debug)]
+struct Directive {
+    scope: Scope,
+    /// Rule IDs to silence; empty silences every rule.
+    rules: Vec<String>,
+    /// Lines a line-scoped directive covers.
+    lines: RangeInclusive<usize>,
+    /// Line and byte range of the comment.
+    line: usize,
+    span: Range<usize>,
+}
+
+impl Directive {
+    /// Parses a comment, returning its scope and rule IDs if it is a directive.
+    fn parse(comment: &str) -> Option<(Scope, Vec<String>)> {
+        let text = comment.trim_start_matches('#').trim();
+        let (scope, rest) = [
+            ("envcheck-disable-next-line", Scope::NextLine),
+            ("envcheck-disable-line", Scope::Line),
+            ("envcheck-disable", Scope::File),
+        ]
+        .into_iter()
+        .find_map(|(keyword, scope)| {
+            let rest = text.strip_prefix(keyword)?;
+            (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some((scope, rest))
+        })?;
+
+        let rules = rest
+            .split("--")
+            .next()
+            .unwrap_or_default()
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|id| !id.is_empty())
+            .map(str::to_uppercase)
+            .collect();
+
+        Some((scope, rules))
+    }
+
+    /// Returns the index of the rule entry that silences `diagnostic`, if any.
+    fn matches(&self, diagnostic: &Diagnostic) -> Option<usize> {
+        let in_scope = match self.scope {
+            Scope::File => true,
+            Scope::Line | Scope::NextLine => diagnostic
+                .line
+                .is_some_and(|line| self.lines.contains(&line)),
+        };
+        if !in_scope {
+            return None;
+        }
+
+        if self.rules.is_empty() {
+            return Some(0);
+        }
+        let id = diagnostic.id.to_string();
+        // Directive IDs are upper-cased; custom rule IDs may not be
+        self.rules
+            .iter()
+            .position(|rule| rule.eq_ignore_ascii_case(&id))
+    }
+}
+
+/// Collects the suppression directives of a file.
+fn directives(env_file: &EnvFile) -> Vec<Directive> {
+    let nodes = env_file.syntax.nodes();
+    let mut directives = Vec::new();
+
+    for (i, node) in nodes.iter().enumerate() {
+        for token in &node.tokens {
+            if !matches!(token.kind, TokenKind::Comment | TokenKind::InlineComment) {
+                continue;
+            }
+            let Some((scope, rules)) = Directive::parse(env_file.syntax.token_text(token)) else {
+                continue;
+            };
+
+            #[allow(clippy::reversed_empty_ranges)]
+            let lines = match scope {
+                Scope::NextLine => nodes.get(i + 1).map_or(1..=0, |n| n.line..=n.end_line),
+                Scope::Line | Scope::File => node.line..=node.end_line,
+            };
+
+            directives.push(Directive {
+                scope,
+                rules,
+                lines,
+                line: node.line,
+                span: token.span.clone(),
+            });
+        }
+    }
+
+    directives
+}
+
+/// Drops diagnostics silenced by suppression comments and reports directives
+/// that silence nothing.
+#[must_use]
+pub fn apply(env_file: &EnvFile, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    let directives = directives(env_file);
+    if directives.is_empty() {
+        return diagnostics;
+    }
+
+    let mut used: Vec<Vec<bool>> = directives
+        .iter()
+        .map(|d| vec![false; d.rules.len().max(1)])
+        .collect();
+
+    let mut kept: Vec<Diagnostic> = diagnostics
+        .into_iter()
+        .filter(|diagnostic| {
+            let mut suppressed = false;
+            for (directive, used) in directives.iter().zip(&mut used) {
+                if let Some(index) = directive.matches(diagnostic) {
+                    used[index] = true;
+                    suppressed = true;
+                }
+            }
+            !suppressed
+        })
+        .collect();
+
+    let unused_id = RuleId::W010.to_string();
+    for (directive, used) in directives.iter().zip(&used) {
+        for (index, _) in used.iter().enumerate().filter(|(_, used)| !**used) {
+            let message = match directive.rules.get(index) {
+                // Silencing W010 itself is never "unused"
+                Some(rule) if *rule == unused_id => continue,
+                Some(rule) => format!("Unused suppression: no {rule} diagnostic to silence"),
+                None => "Unused suppression: no diagnostic to silence".to_string(),
+            };
+
+            let unused = Diagnostic {
+                id: RuleId::W010,
+                severity: Severity::Warning,
+                message,
+                path: env_file.path.clone(),
+                line: Some(directive.line),
+                span: Some(Span::new(env_file, directive.span.clone())),
+                labels: Vec::new(),
+                constraint: None,
+                fix: None,
+            };
+            if !directives.iter().any(|d| d.matches(&unused).is_some()) {
+                kept.push(unused);
+            }
+        }
+    }
+
+    kept
+}
@@ -1,5 +1,5 @@
-use crate::parser::EnvFile;
-use crate::rules::{Diagnostic, Rule, RuleId, Severity};
+use crate::parser::{EnvFile, QuoteStyle};
+use crate::rules::{Diagnostic, Rule, RuleId, Severity, Span};
 
 pub struct SyntaxRule;
 
@@ -19,9 +19,22 @@ impl Rule for SyntaxRule {
                 continue;
             }
 
+            // Continuation lines of a multi-line quoted value are not assignments
+            if env_file
+                .multiline_var_at(line_num)
+                .is_some_and(|v| v.line != line_num)
+            {
+                continue;
+            }
+
             // Remove optional export prefix for validation
             let content = trimmed.strip_prefix("export ").unwrap_or(trimmed).trim();
 
+            // Byte offsets of the trimmed line and of the content after `export`
+            let trimmed_start =
+                env_file.syntax.line_span(line_num).start + (line.len() - line.trim_start().len());
+            let content_start = trimmed_start + (trimmed.len() - content.len());
+
             // Basic syntax check: must contain '=' and not start with =
             if !content.contains('=') {
                 diagnostics.push(Diagnostic {
@@ -32,6 +45,13 @@ impl Rule for SyntaxRule {
                     ),
                     path: env_file.path.clone(),
                     line: Some(line_num),
+                    span: Some(Span::new(
+                        env_file,
+                        trimmed_start..trimmed_start + trimmed.len(),
+                    )),
+                    labels: Vec::new(),
+                    constraint: None,
+                    fix: None,
                 });
             } else if content.starts_with('=') {
                 diagnostics.push(Diagnostic {
@@ -40,6 +60,10 @@ impl Rule for SyntaxRule {
                     message: "Invalid syntax: key name cannot be empty".to_string(),
                     path: env_file.path.clone(),
                     line: Some(line_num),
+                    span: Some(Span::new(env_file, content_start..content_start + 1)),
+                    labels: Vec::new(),
+                    constraint: None,
+                    fix: None,
                 });
             } else {
                 // Check valid key format (alphanumeric + underscore + dot/dash strictly?)
@@ -47,6 +71,7 @@ impl Rule for SyntaxRule {
                 // But some systems allow dots or dashes. Bash doesn't like dots/dashes in exports.
                 // let's check for spaces in the key part
                 let key_part = content.split_once('=').unwrap().0.trim();
+                let key_span = Span::new(env_file, content_start..content_start + key_part.len());
 
                 // "INVALID KEY=value" -> key part is "INVALID KEY"
                 if key_part.contains(char::is_whitespace) {
@@ -56,6 +81,10 @@ impl Rule for SyntaxRule {
                         message: format!("Invalid syntax: key '{key_part}' contains whitespace"),
                         path: env_file.path.clone(),
                         line: Some(line_num),
+                        span: Some(key_span),
+                        labels: Vec::new(),
+                        constraint: None,
+                        fix: None,
                     });
                 }
 
@@ -69,11 +98,41 @@ impl Rule for SyntaxRule {
                         ),
                         path: env_file.path.clone(),
                         line: Some(line_num),
+                        span: Some(key_span),
+                        labels: Vec::new(),
+                        constraint: None,
+                        fix: None,
                     });
                 }
             }
         }
 
+        // A value that opens a quote but never closes it is read literally, quote included
+        let features = env_file.dialect.features();
+        let opens_quote = |c: char| match c {
+            '"' | '\'' => features.quotes,
+            '`' => features.quotes && features.backticks,
+            _ => false,
+        };
+        for var in &env_file.vars {
+            if var.quote == QuoteStyle::None && var.raw_value.starts_with(opens_quote) {
+                diagnostics.push(Diagnostic {
+                    id: self.id(),
+                    severity: Severity::Error,
+                    message: format!(
+                        "Invalid syntax: unterminated quoted value for '{}'",
+                        var.key
+                    ),
+                    path: env_file.path.clone(),
+                    line: Some(var.line),
+                    span: Some(Span::new(env_file, var.raw_span.clone())),
+                    labels: Vec::new(),
+                    constraint: None,
+                    fix: None,
+                });
+            }
+        }
+
         diagnostics
     }
 }
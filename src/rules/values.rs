@@ -0,0 +1,188 @@
+//! Value comparisons across environments for `envcheck compare`.
+//!
+//! Keys listed under `compare.must_differ` (secrets) must not share a value
+//! between files (E008); keys under `compare.must_equal` (feature flags,
+//! service names) must not differ (W018). With `--values`, any other difference
+//! is reported as info (W019). Values are redacted in messages.
+
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::CompareConfig;
+use crate::parser::interpolate::Interpolator;
+use crate::parser::{EnvFile, EnvVar};
+use crate::rules::secrets::is_template;
+use crate::rules::{Diagnostic, RuleId, Severity, Span};
+
+/// How values appear in compare messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Redaction {
+    /// The first 32 bits of the SHA-256 hash: `hash:1a2b3c4d`
+    #[default]
+    Hash,
+    /// Only the length: `<12 chars>`
+    Length,
+    /// A fixed mask: `****`
+    Mask,
+    /// The value itself
+    None,
+}
+
+impl Redaction {
+    /// Renders `value` for a message.
+    #[must_use]
+    pub fn apply(self, value: &str) -> String {
+        match self {
+            Self::Hash => {
+                let digest = Sha256::digest(value.as_bytes());
+                let prefix: String = digest[..4].iter().map(|b| format!("{b:02x}")).collect();
+                format!("hash:{prefix}")
+            },
+            Self::Length => format!("<{} chars>", value.chars().count()),
+            Self::Mask => "****".to_string(),
+            Self::None => format!("`{value}`"),
+        }
+    }
+}
+
+/// The non-empty expanded value of each key, with the entry that sets it.
+type Values<'a> = HashMap<&'a str, (&'a EnvVar, String)>;
+
+/// Compares the values of the same key across files.
+pub struct ValueComparison {
+    must_differ: Vec<glob::Pattern>,
+    must_equal: Vec<glob::Pattern>,
+    redaction: Redaction,
+    report_differences: bool,
+}
+
+impl ValueComparison {
+    /// `report_differences` enables W019. The key globs were checked by
+    /// [`Config::compile_rules`](crate::config::Config::compile_rules).
+    #[must_use]
+    pub fn new(config: &CompareConfig, redaction: Redaction, report_differences: bool) -> Self {
+        let patterns = |globs: &[String]| {
+            globs
+                .iter()
+                .filter_map(|p| glob::Pattern::new(p).ok())
+                .collect()
+        };
+        Self {
+            must_differ: patterns(&config.must_differ),
+            must_equal: patterns(&config.must_equal),
+            redaction,
+            report_differences,
+        }
+    }
+
+    /// Checks every file against the files before it, on expanded values.
+    /// Templates and empty values are skipped, since they hold no real settings.
+    #[must_use]
+    pub fn check(&self, env_files: &[EnvFile]) -> Vec<Diagnostic> {
+        let files: Vec<(&EnvFile, Values<'_>)> = env_files
+            .iter()
+            .filter(|f| !is_template(&f.path))
+            .map(|f| {
+                let values = Interpolator::new().resolve_vars(f);
+                let vars = f
+                    .vars
+                    .iter()
+                    .zip(values)
+                    .filter(|(_, value)| !value.is_empty())
+                    .map(|(v, value)| (v.key.as_str(), (v, value)))
+                    .collect();
+                (f, vars)
+            })
+            .collect();
+
+        let mut diagnostics = Vec::new();
+        for (i, (env_file, vars)) in files.iter().enumerate() {
+            // The effective (last) definition of each key, in file order
+            let effective = env_file.vars.iter().filter_map(|v| {
+                vars.get(v.key.as_str())
+                    .filter(|(e, _)| std::ptr::eq(*e, v))
+            });
+            for (var, value) in effective {
+                let key = var.key.as_str();
+                let mut earlier = files[..i]
+                    .iter()
+                    .filter_map(|(f, vars)| vars.get(key).map(|(_, v)| (*f, v)));
+
+                let diagnostic = if self.must_differ.iter().any(|p| p.matches(key)) {
+                    earlier.find(|(_, v)| *v == value).map(|(other, _)| {
+                        let message = format!(
+                            "Key '{key}' has the same value as in {} ({})",
+                            other.path.display(),
+                            self.redaction.apply(value)
+                        );
+                        (RuleId::E008, Severity::Error, message)
+                    })
+                } else {
+                    earlier
+                        .next()
+                        .filter(|(_, v)| *v != value)
+                        .and_then(|(other, v)| self.difference(key, other, v, value))
+                };
+
+                if let Some((id, severity, message)) = diagnostic {
+                    // A span would make text output print the line, value included
+                    let span = (self.redaction == Redaction::None)
+                        .then(|| Span::new(env_file, var.raw_span.clone()));
+                    diagnostics.push(Diagnostic {
+                        id,
+                        severity,
+                        message,
+                        path: env_file.path.clone(),
+                        line: Some(var.line),
+                        span,
+                        labels: Vec::new(),
+                        constraint: None,
+                        fix: None,
+                    });
+                }
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Reports a value that differs from the first file defining the key.
+    fn difference(
+        &self,
+        key: &str,
+        other: &EnvFile,
+        before: &str,
+        after: &str,
+    ) -> Option<(RuleId, Severity, String)> {
+        let values = format!(
+            "{} vs {}",
+            self.redaction.apply(before),
+            self.redaction.apply(after)
+        );
+        if self.must_equal.iter().any(|p| p.matches(key)) {
+            Some((
+                RuleId::W018,
+                Severity::Warning,
+                format!(
+                    "Key '{key}' must be equal across environments but differs from {} ({values})",
+                    other.path.display()
+                ),
+            ))
+        } else if self.report_differences {
+            Some((
+                RuleId::W019,
+                Severity::Info,
+                format!(
+                    "Value of '{key}' differs from {} ({values})",
+                    other.path.display()
+                ),
+            ))
+        } else {
+            None
+        }
+    }
+}
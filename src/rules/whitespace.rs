@@ -1,5 +1,5 @@
 use crate::parser::EnvFile;
-use crate::rules::{Diagnostic, Rule, RuleId, Severity};
+use crate::rules::{Diagnostic, Edit, Fix, Rule, RuleId, Severity, Span};
 
 pub struct TrailingWhitespaceRule;
 
@@ -18,13 +18,30 @@ impl Rule for TrailingWhitespaceRule {
             // But stripping whitespace is standard behavior for parsers.
             // The requirement says "Trailing whitespace" warning.
 
+            // Whitespace before the closing quote of a multi-line value is part of the value
+            if env_file
+                .multiline_var_at(line_num)
+                .is_some_and(|v| v.end_line != line_num)
+            {
+                continue;
+            }
+
             if line.len() != line.trim_end().len() {
+                let line_span = env_file.syntax.line_span(line_num);
+                let start = line_span.start + line.trim_end().len();
                 diagnostics.push(Diagnostic {
                     id: self.id(),
                     severity: Severity::Warning,
                     message: "Line contains trailing whitespace".to_string(),
                     path: env_file.path.clone(),
                     line: Some(line_num),
+                    span: Some(Span::new(env_file, start..line_span.end)),
+                    labels: Vec::new(),
+                    constraint: None,
+                    fix: Some(Fix::new(
+                        "Remove trailing whitespace",
+                        vec![Edit::delete(env_file, start..line_span.end)],
+                    )),
                 });
             }
         }
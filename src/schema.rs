@@ -0,0 +1,394 @@
+//! Typed contracts for .env keys, loaded from `envcheck.schema.toml` or `envcheck.schema.yaml`.
+//!
+//! ```toml
+//! [keys.PORT]
+//! type = "port"
+//! required = true
+//! description = "HTTP listen port"
+//!
+//! [keys.LOG_LEVEL]
+//! type = "enum"
+//! values = ["debug", "info", "warn", "error"]
+//! default = "info"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{EnvCheckError, Result};
+
+/// File names searched for when no schema path is configured.
+pub const SCHEMA_FILE_NAMES: [&str; 3] = [
+    "envcheck.schema.toml",
+    "envcheck.schema.yaml",
+    "envcheck.schema.yml",
+];
+
+/// The type a value must parse as.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueType {
+    /// Any text; `min`/`max` bound its length.
+    #[default]
+    String,
+    /// A whole number; `min`/`max` bound its value.
+    Int,
+    /// `true` or `false`.
+    Bool,
+    /// A TCP/UDP port between 1 and 65535.
+    Port,
+    /// An absolute URL such as `https://example.com`.
+    Url,
+    /// An email address.
+    Email,
+    /// A duration such as `30s` or `1h30m`; `min`/`max` are in seconds.
+    Duration,
+    /// A JSON document.
+    Json,
+    /// One of `values`.
+    Enum,
+    /// A string matching `pattern`.
+    Regex,
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::String => "string",
+            Self::Int => "int",
+            Self::Bool => "bool",
+            Self::Port => "port",
+            Self::Url => "url",
+            Self::Email => "email",
+            Self::Duration => "duration",
+            Self::Json => "json",
+            Self::Enum => "enum",
+            Self::Regex => "regex",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A scalar written in the schema, such as a default.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Scalar {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Int(i) => write!(f, "{i}"),
+            Self::Float(x) => write!(f, "{x}"),
+            Self::String(s) => f.write_str(s),
+        }
+    }
+}
+
+/// The contract of a single key.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeySchema {
+    #[serde(rename = "type")]
+    pub value_type: ValueType,
+    /// The key must be present.
+    pub required: bool,
+    /// Value used when the key is absent; documentation only.
+    pub default: Option<Scalar>,
+    /// Lower bound: a value for numbers, seconds for durations, a length for strings.
+    pub min: Option<f64>,
+    /// Upper bound, like `min`.
+    pub max: Option<f64>,
+    /// Allowed values for `enum`.
+    pub values: Vec<String>,
+    /// Regular expression for `regex`; must match the whole value.
+    pub pattern: Option<String>,
+    pub description: Option<String>,
+    /// `pattern` anchored to the whole value, compiled when the schema is loaded.
+    #[serde(skip)]
+    regex: Option<Regex>,
+}
+
+/// A failed constraint, reported with the key it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub key: String,
+    /// The declared type, e.g. `port`.
+    #[serde(rename = "type")]
+    pub value_type: ValueType,
+    /// The constraint that failed, e.g. `between 1 and 65535`.
+    pub expected: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl KeySchema {
+    /// Compiles `pattern` so values are checked without recompiling it.
+    fn compile(&mut self) -> std::result::Result<(), regex::Error> {
+        if let Some(pattern) = &self.pattern {
+            self.regex = Some(Regex::new(&format!("^(?:{pattern})$"))?);
+        }
+        Ok(())
+    }
+
+    /// Checks the value of `key` against the contract.
+    pub fn validate(&self, key: &str, value: &str) -> std::result::Result<(), Violation> {
+        self.check(value)
+            .map_err(|expected| self.violation(key, expected))
+    }
+
+    /// Describes a failed constraint of `key`.
+    #[must_use]
+    pub fn violation(&self, key: &str, expected: impl Into<String>) -> Violation {
+        Violation {
+            key: key.to_string(),
+            value_type: self.value_type,
+            expected: expected.into(),
+            description: self.description.clone(),
+        }
+    }
+
+    fn check(&self, value: &str) -> std::result::Result<(), String> {
+        match self.value_type {
+            ValueType::String => self.check_range(value.chars().count() as f64, "length "),
+            ValueType::Int => {
+                let number: i64 = value.parse().map_err(|_| "an integer".to_string())?;
+                #[allow(clippy::cast_precision_loss)]
+                self.check_range(number as f64, "")
+            },
+            ValueType::Bool => match value {
+                "true" | "false" => Ok(()),
+                _ => Err("`true` or `false`".to_string()),
+            },
+            ValueType::Port => match value.parse::<u16>() {
+                Ok(port) if port > 0 => self.check_range(f64::from(port), ""),
+                _ => Err("a port between 1 and 65535".to_string()),
+            },
+            ValueType::Url => {
+                static URL: OnceLock<Regex> = OnceLock::new();
+                let url = cached(&URL, r"^[A-Za-z][A-Za-z0-9+.\-]*://[^\s/?#]+[^\s]*$");
+                if url.is_match(value) {
+                    Ok(())
+                } else {
+                    Err("an absolute URL like `https://host/path`".to_string())
+                }
+            },
+            ValueType::Email => {
+                static EMAIL: OnceLock<Regex> = OnceLock::new();
+                let email = cached(&EMAIL, r"^[^@\s]+@[^@\s]+\.[^@\s]+$");
+                if email.is_match(value) {
+                    Ok(())
+                } else {
+                    Err("an email address".to_string())
+                }
+            },
+            ValueType::Duration => {
+                let seconds = parse_duration(value)
+                    .ok_or_else(|| "a duration like `30s`, `5m` or `1h30m`".to_string())?;
+                self.check_range(seconds, "seconds ")
+            },
+            ValueType::Json => serde_json::from_str::<serde_json::Value>(value)
+                .map(|_| ())
+                .map_err(|_| "valid JSON".to_string()),
+            ValueType::Enum => {
+                if self.values.iter().any(|v| v == value) {
+                    Ok(())
+                } else {
+                    Err(format!("one of: {}", self.values.join(", ")))
+                }
+            },
+            ValueType::Regex => match (&self.regex, &self.pattern) {
+                (Some(re), Some(pattern)) if !re.is_match(value) => {
+                    Err(format!("a value matching `{pattern}`"))
+                },
+                _ => Ok(()),
+            },
+        }
+    }
+
+    fn check_range(&self, number: f64, what: &str) -> std::result::Result<(), String> {
+        match (self.min, self.max) {
+            (Some(min), Some(max)) if number < min || number > max => {
+                Err(format!("{what}between {min} and {max}"))
+            },
+            (Some(min), None) if number < min => Err(format!("{what}at least {min}")),
+            (None, Some(max)) if number > max => Err(format!("{what}at most {max}")),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Compiles a built-in pattern on first use.
+fn cached(cell: &'static OnceLock<Regex>, pattern: &str) -> &'static Regex {
+    cell.get_or_init(|| Regex::new(pattern).expect("valid built-in regex"))
+}
+
+/// Parses durations like `500ms`, `30s`, `5m`, `1h30m` or `2d` into seconds.
+fn parse_duration(value: &str) -> Option<f64> {
+    static DURATION: OnceLock<Regex> = OnceLock::new();
+    static PART: OnceLock<Regex> = OnceLock::new();
+    if !cached(&DURATION, r"^(?:(\d+(?:\.\d+)?)(ms|s|m|h|d))+$").is_match(value) {
+        return None;
+    }
+
+    let part = cached(&PART, r"(\d+(?:\.\d+)?)(ms|s|m|h|d)");
+    let mut seconds = 0.0;
+    for caps in part.captures_iter(value) {
+        let amount: f64 = caps[1].parse().ok()?;
+        seconds += amount
+            * match &caps[2] {
+                "ms" => 0.001,
+                "s" => 1.0,
+                "m" => 60.0,
+                "h" => 3600.0,
+                _ => 86400.0,
+            };
+    }
+    Some(seconds)
+}
+
+/// The contracts of all declared keys.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnvSchema {
+    pub keys: BTreeMap<String, KeySchema>,
+    /// Path the schema was loaded from.
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl EnvSchema {
+    /// Loads a schema from a TOML or YAML file, chosen by extension.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| EnvCheckError::read_error(path, e))?;
+        let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+
+        let mut schema: Self = if is_toml {
+            toml::from_str(&content).map_err(|e| EnvCheckError::schema_error(path, e))?
+        } else {
+            serde_yaml::from_str(&content).map_err(|e| EnvCheckError::schema_error(path, e))?
+        };
+
+        for (key, contract) in &mut schema.keys {
+            if contract.value_type == ValueType::Enum && contract.values.is_empty() {
+                return Err(EnvCheckError::schema_error(
+                    path,
+                    format!("key '{key}' has type enum but no values"),
+                ));
+            }
+            contract
+                .compile()
+                .map_err(|e| EnvCheckError::schema_error(path, e))?;
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            ..schema
+        })
+    }
+
+    /// Finds a schema file in `start` or its parents.
+    #[must_use]
+    pub fn find(start: &Path) -> Option<PathBuf> {
+        let mut current = start.canonicalize().ok();
+
+        while let Some(dir) = current {
+            for name in SCHEMA_FILE_NAMES {
+                let candidate = dir.join(name);
+                if candidate.exists() {
+                    return Some(candidate);
+                }
+            }
+            current = dir.parent().map(Path::to_path_buf);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(toml: &str) -> KeySchema {
+        let mut key: KeySchema = toml::from_str(toml).unwrap();
+        key.compile().unwrap();
+        key
+    }
+
+    #[test]
+    fn test_port_and_int() {
+        let port = key("type = \"port\"");
+        assert!(port.validate("K", "8080").is_ok());
+        assert!(port.validate("K", "80a").is_err());
+        assert!(port.validate("K", "0").is_err());
+
+        let workers = key("type = \"int\"\nmin = 1\nmax = 64");
+        assert!(workers.validate("K", "8").is_ok());
+        assert_eq!(
+            workers.validate("K", "65").unwrap_err().expected,
+            "between 1 and 64"
+        );
+    }
+
+    #[test]
+    fn test_bool_enum_and_regex() {
+        assert!(key("type = \"bool\"").validate("K", "yes").is_err());
+        assert!(key("type = \"bool\"").validate("K", "true").is_ok());
+
+        let level = key("type = \"enum\"\nvalues = [\"debug\", \"info\"]");
+        assert_eq!(
+            level.validate("K", "trace").unwrap_err().expected,
+            "one of: debug, info"
+        );
+
+        let region = key("type = \"regex\"\npattern = \"[a-z]{2}-[a-z]+-\\\\d\"");
+        assert!(region.validate("K", "eu-west-1").is_ok());
+        assert!(region.validate("K", "eu-west-1x").is_err());
+    }
+
+    #[test]
+    fn test_url_email_json_duration() {
+        assert!(key("type = \"url\"")
+            .validate("K", "https://example.com/x")
+            .is_ok());
+        assert!(key("type = \"url\"").validate("K", "example.com").is_err());
+        assert!(key("type = \"email\"")
+            .validate("K", "ops@example.com")
+            .is_ok());
+        assert!(key("type = \"json\"").validate("K", "{\"a\": 1}").is_ok());
+        assert!(key("type = \"json\"").validate("K", "{a: 1}").is_err());
+
+        let timeout = key("type = \"duration\"\nmax = 3600");
+        assert!(timeout.validate("K", "1h").is_ok());
+        assert!(timeout.validate("K", "1h30m").is_err());
+        assert!(timeout.validate("K", "ten").is_err());
+        assert_eq!(parse_duration("1m30s"), Some(90.0));
+    }
+
+    #[test]
+    fn test_schema_formats() {
+        let toml = "[keys.PORT]\ntype = \"port\"\nrequired = true\ndefault = 8080\n";
+        let schema: EnvSchema = toml::from_str(toml).unwrap();
+        assert!(schema.keys["PORT"].required);
+        assert_eq!(
+            schema.keys["PORT"].default.as_ref().unwrap().to_string(),
+            "8080"
+        );
+
+        let yaml = "keys:\n  DEBUG:\n    type: bool\n    default: false\n";
+        let schema: EnvSchema = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(schema.keys["DEBUG"].value_type, ValueType::Bool);
+    }
+}
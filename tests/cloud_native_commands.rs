@@ -1,7 +1,12 @@
+#![allow(deprecated)]
 use crate::common::TempEnvDir;
 use envcheck::commands::{actions, argo, helm};
+use envcheck::config::Config;
 use envcheck::output::Format;
 
+use assert_cmd::Command;
+use predicates::prelude::*;
+
 mod common;
 
 #[test]
@@ -27,7 +32,7 @@ env:
 
     // We expect it to print missing "CI_KEY"
     // For now just check it runs without panic
-    let result = actions::run(temp.path(), &env_path, Format::Text);
+    let result = actions::run(temp.path(), &env_path, Format::Text, &Config::default());
     assert!(result.is_ok());
 }
 
@@ -45,10 +50,56 @@ env:
 
     let env_path = temp.create_env_file(".env", "OTHER=1\n").unwrap();
 
-    let result = helm::run(temp.path(), &env_path, Format::Text);
+    let result = helm::run(temp.path(), &env_path, Format::Text, &Config::default());
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_helm_command_skips_optional_env_vars() {
+    let temp = TempEnvDir::new().unwrap();
+    temp.create_env_file(
+        "values.yaml",
+        "env:\n  DB_HOST: \"localhost\"\n  CACHE_URL: \"redis\"\n",
+    )
+    .unwrap();
+    temp.create_env_file(".env", "# CACHE_URL=redis://localhost\n")
+        .unwrap();
+
+    Command::cargo_bin("envcheck")
+        .unwrap()
+        .current_dir(temp.path())
+        .arg("helm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DB_HOST"))
+        .stdout(predicate::str::contains("CACHE_URL").not());
+}
+
+#[test]
+fn test_helm_command_uses_dialect() {
+    let temp = TempEnvDir::new().unwrap();
+    temp.create_env_file("values.yaml", "env:\n  DB_HOST: \"localhost\"\n")
+        .unwrap();
+    temp.create_env_file(".env", "export DB_HOST=db\n").unwrap();
+
+    Command::cargo_bin("envcheck")
+        .unwrap()
+        .current_dir(temp.path())
+        .arg("helm")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DB_HOST").not());
+
+    // Docker doesn't understand `export`, so the key isn't defined
+    Command::cargo_bin("envcheck")
+        .unwrap()
+        .current_dir(temp.path())
+        .args(["helm", "--dialect", "docker"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("DB_HOST"));
+}
+
 #[test]
 fn test_argo_command_detects_missing_env_vars() {
     let temp = TempEnvDir::new().unwrap();
@@ -71,6 +122,6 @@ spec:
 
     let env_path = temp.create_env_file(".env", "OTHER=1\n").unwrap();
 
-    let result = argo::run(temp.path(), &env_path, Format::Text);
+    let result = argo::run(temp.path(), &env_path, Format::Text, &Config::default());
     assert!(result.is_ok());
 }
@@ -121,3 +121,291 @@ fn test_compare_requires_multiple_files() {
         // Clap error message: "error: 2 values required..."
         .stderr(predicate::str::contains("required").and(predicate::str::contains("2")));
 }
+
+#[test]
+fn test_compare_validates_schema() {
+    let temp_dir = common::TempEnvDir::new().expect("Failed to create temp dir");
+    let schema = temp_dir
+        .create_env_file("env.schema.toml", "[keys.PORT]\ntype = \"port\"\n")
+        .unwrap();
+    let example = temp_dir
+        .create_env_file(".env.example", "PORT=8080\n")
+        .unwrap();
+    let prod = temp_dir.create_env_file(".env.prod", "PORT=80a\n").unwrap();
+
+    envcheck_cmd()
+        .arg("compare")
+        .args([&example, &prod])
+        .arg("--schema")
+        .arg(&schema)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("E005").count(1))
+        .stdout(predicate::str::contains(
+            "Key 'PORT' must be a port between 1 and 65535",
+        ));
+}
+
+#[test]
+fn test_compare_symmetric_reports_extra_keys() {
+    let temp_dir = common::TempEnvDir::new().unwrap();
+    temp_dir
+        .create_env_file(".env.example", "DB_HOST=\nDB_PORT=\n")
+        .unwrap();
+    temp_dir
+        .create_env_file(".env.prod", "DB_HOST=db\nLEGACY_FLAG=1\n")
+        .unwrap();
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["compare", "--compact", ".env.example", ".env.prod"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Missing key 'DB_PORT'"))
+        .stdout(predicate::str::contains("W017").not());
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["compare", "--compact", "--mode", "symmetric"])
+        .args([".env.example", ".env.prod"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Missing key 'DB_PORT'"))
+        .stdout(predicate::str::contains(
+            "warning[W017]: Extra key 'LEGACY_FLAG' (not in .env.example)\n  --> .env.prod:2:1",
+        ));
+}
+
+#[test]
+fn test_compare_matrix_formats() {
+    let temp_dir = common::TempEnvDir::new().unwrap();
+    temp_dir
+        .create_env_file(".env.example", "DB_HOST=\nDB_PORT=\n")
+        .unwrap();
+    temp_dir
+        .create_env_file(".env.prod", "DB_HOST=db\nEXTRA=1\n")
+        .unwrap();
+    let files = ["compare", "--mode", "matrix", ".env.example", ".env.prod"];
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(files)
+        .assert()
+        .success()
+        .stdout(
+            "KEY      .env.example  .env.prod\n\
+             DB_HOST  ✓             ✓\n\
+             DB_PORT  ✓             ✗\n\
+             EXTRA    ✗             ✓\n",
+        );
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(files)
+        .args(["--format", "markdown"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "| Key | .env.example | .env.prod |\n|-----|---|---|\n| `DB_HOST` | ✅ | ✅ |\n",
+        ))
+        .stdout(predicate::str::contains("| `EXTRA` | ❌ | ✅ |"));
+
+    let output = envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(files)
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["files"][1], ".env.prod");
+    assert_eq!(json["keys"][1]["key"], "DB_PORT");
+    assert_eq!(json["keys"][1]["present"], serde_json::json!([true, false]));
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(files)
+        .args(["--format", "sarif"])
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains(
+            "the key matrix cannot be written as sarif",
+        ));
+}
+
+#[test]
+fn test_compare_value_rules() {
+    let temp_dir = common::TempEnvDir::new().unwrap();
+    temp_dir
+        .create_env_file(
+            ".envcheckrc.yaml",
+            "compare:\n  must_differ: [\"*_PASSWORD\"]\n  must_equal: [SERVICE_NAME]\n",
+        )
+        .unwrap();
+    temp_dir
+        .create_env_file(".env.example", "DB_PASSWORD=changeme\nSERVICE_NAME=\n")
+        .unwrap();
+    temp_dir
+        .create_env_file(
+            ".env.staging",
+            "DB_PASSWORD=hunter2\nSERVICE_NAME=api\nLOG_LEVEL=debug\n",
+        )
+        .unwrap();
+    temp_dir
+        .create_env_file(
+            ".env.prod",
+            "DB_PASSWORD=hunter2\nSERVICE_NAME=api-prod\nLOG_LEVEL=info\n",
+        )
+        .unwrap();
+    let files = [".env.example", ".env.staging", ".env.prod"];
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .arg("compare")
+        .args(files)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "error[E008]: Key 'DB_PASSWORD' has the same value as in .env.staging (hash:",
+        ))
+        .stdout(predicate::str::contains(
+            "warning[W018]: Key 'SERVICE_NAME' must be equal across environments but differs from .env.staging",
+        ))
+        .stdout(predicate::str::contains("hunter2").not())
+        .stdout(predicate::str::contains("W019").not());
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["compare", "--values", "--redact", "length"])
+        .args(files)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Value of 'LOG_LEVEL' differs from .env.staging (<5 chars> vs <4 chars>)",
+        ))
+        .stdout(predicate::str::contains("W019").count(1));
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["compare", "--redact", "none"])
+        .args(files)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("(`api` vs `api-prod`)"));
+}
+
+#[test]
+fn test_compare_values_after_interpolation() {
+    let temp_dir = common::TempEnvDir::new().unwrap();
+    temp_dir
+        .create_env_file(".envcheckrc.yaml", "compare:\n  must_differ: [DB_URL]\n")
+        .unwrap();
+    temp_dir
+        .create_env_file(
+            ".env.staging",
+            "DB_HOST=staging-db\nDB_URL=postgres://${DB_HOST}/app\n",
+        )
+        .unwrap();
+    temp_dir
+        .create_env_file(
+            ".env.prod",
+            "DB_HOST=prod-db\nDB_URL=postgres://${DB_HOST}/app\n",
+        )
+        .unwrap();
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["compare", ".env.staging", ".env.prod"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("E008").not());
+}
+
+#[test]
+fn test_compare_optional_keys() {
+    let temp_dir = common::TempEnvDir::new().unwrap();
+    temp_dir
+        .create_env_file(".envcheckrc.yaml", "optional: [\"FEATURE_*\"]\n")
+        .unwrap();
+    temp_dir
+        .create_env_file(
+            ".env.example",
+            "DB_HOST=\n# @optional\nDEBUG=\nFEATURE_X=\n# SENTRY_DSN=\n",
+        )
+        .unwrap();
+    temp_dir
+        .create_env_file(".env.prod", "SENTRY_DSN=https://sentry\n")
+        .unwrap();
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["compare", "--mode", "symmetric", "--format", "json"])
+        .args([".env.example", ".env.prod"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Missing key 'DB_HOST'"))
+        .stdout(predicate::str::contains("Missing optional key 'DEBUG'"))
+        .stdout(predicate::str::contains("Missing optional key 'FEATURE_X'"))
+        .stdout(predicate::str::contains("\"severity\": \"warning\"").count(1))
+        .stdout(predicate::str::contains("W017").not());
+}
+
+#[test]
+fn test_compare_against_git_revision() {
+    let temp_dir = common::TempEnvDir::new().unwrap();
+    let dir = temp_dir.path();
+    common::init_git_repo(dir);
+    temp_dir
+        .create_env_file(".env.example", "DB_URL=\nLEGACY=\nPORT=\n")
+        .unwrap();
+    common::git(dir, &["add", "."]);
+    common::git(dir, &["commit", "-q", "-m", "init"]);
+    temp_dir
+        .create_env_file(".env.example", "DATABASE_URL=\nPORT=\nSENTRY_DSN=\n")
+        .unwrap();
+
+    envcheck_cmd()
+        .current_dir(dir)
+        .args(["compare", "--git-ref", "main", ".env.example"])
+        .assert()
+        .success()
+        .stdout(
+            ".env.example (main → working copy)\n  + SENTRY_DSN\n  - LEGACY\n  ~ DB_URL → DATABASE_URL\n",
+        );
+
+    envcheck_cmd()
+        .current_dir(dir)
+        .args([
+            "compare",
+            "--git-ref",
+            "main",
+            "--format",
+            "json",
+            ".env.example",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"from\": \"DB_URL\""))
+        .stdout(predicate::str::contains(
+            "\"added\": [\n      \"SENTRY_DSN\"\n    ]",
+        ));
+
+    envcheck_cmd()
+        .current_dir(dir)
+        .args(["compare", "--git-ref", "main", "--format", "github"])
+        .arg(".env.example")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "key changes cannot be written as github",
+        ));
+
+    envcheck_cmd()
+        .current_dir(dir)
+        .args(["compare", "--git-ref", "no-such-ref", ".env.example"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "git rev-parse --verify no-such-ref^{commit}",
+        ));
+}
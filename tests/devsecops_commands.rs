@@ -1,5 +1,6 @@
 use crate::common::TempEnvDir;
 use envcheck::commands::{ansible, terraform};
+use envcheck::config::Config;
 use envcheck::output::Format;
 
 mod common;
@@ -34,7 +35,7 @@ fn test_terraform_command_detects_missing_env_vars() {
     // Let's rely on `assert_cmd` in a separate test function if we want to check output.
     // Or just check that it runs successfully.
 
-    let result = terraform::run(temp.path(), &env_path, Format::Text);
+    let result = terraform::run(temp.path(), &env_path, Format::Text, &Config::default());
     assert!(result.is_ok());
 }
 
@@ -53,6 +54,6 @@ fn test_ansible_command_detects_missing_lookups() {
     // Create .env file without API_KEY
     let env_path = temp.create_env_file(".env", "OTHER=1\n").unwrap();
 
-    let result = ansible::run(temp.path(), &env_path, Format::Text);
+    let result = ansible::run(temp.path(), &env_path, Format::Text, &Config::default());
     assert!(result.is_ok());
 }
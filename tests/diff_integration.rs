@@ -0,0 +1,168 @@
+#![allow(deprecated)]
+//! Integration tests for the `envcheck diff` command
+
+mod common;
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+/// Helper to get the envcheck binary command
+fn envcheck_cmd() -> Command {
+    Command::cargo_bin("envcheck").expect("Failed to find envcheck binary")
+}
+
+#[test]
+fn test_diff_reports_semantic_changes() {
+    let temp_dir = common::TempEnvDir::new().unwrap();
+    temp_dir
+        .create_env_file(
+            "old.env",
+            "# Database\nDB_URL=postgres://db\nPORT=8080\nHOST=\nLEGACY=\n",
+        )
+        .unwrap();
+    temp_dir
+        .create_env_file(
+            "new.env",
+            "# Database\nHOST=\nexport PORT=\"8081\"\nDATABASE_URL=postgres://db\nSENTRY_DSN=\n",
+        )
+        .unwrap();
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["diff", "old.env", "new.env", "--redact", "none"])
+        .assert()
+        .success()
+        .stdout(
+            "--- old.env\n+++ new.env\n  + SENTRY_DSN\n  - LEGACY\n  ~ DB_URL → DATABASE_URL\n  * PORT: `8080` → `8081`\n  keys reordered\n",
+        );
+
+    // Values are hashed by default
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["diff", "--format", "json", "old.env", "new.env"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"from\": \"hash:"))
+        .stdout(predicate::str::contains("8080").not())
+        .stdout(predicate::str::contains("\"comments_changed\": false"));
+
+    // Formatting alone is not a change
+    temp_dir
+        .create_env_file(
+            "same.env",
+            "# Database\n\nDB_URL='postgres://db'\nPORT=8080\nHOST=\nLEGACY=\n",
+        )
+        .unwrap();
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["diff", "old.env", "same.env"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("no semantic changes"));
+}
+
+#[test]
+fn test_diff_breaking_reports_removed_required_keys() {
+    let temp_dir = common::TempEnvDir::new().unwrap();
+    temp_dir
+        .create_env_file("old.env", "DB_HOST=\n# @optional\nDEBUG=\nPORT=\n")
+        .unwrap();
+    temp_dir.create_env_file("new.env", "PORT=\n").unwrap();
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["diff", "--breaking", "old.env", "new.env"])
+        .assert()
+        .failure()
+        .stdout("--- old.env\n+++ new.env\n  ! DB_HOST (required) removed\n")
+        .stderr(predicate::str::contains(
+            "1 required key(s) removed: DB_HOST",
+        ));
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["diff", "--breaking", "new.env", "old.env"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("no breaking changes"));
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["diff", "old.env", "new.env", "old.env"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("expected 2 files"));
+
+    // A single file is only an unmerged path when git runs the driver
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .env_remove("GIT_DIFF_PATH_TOTAL")
+        .args(["diff", "old.env"])
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains("expected 2 files"));
+
+    envcheck_cmd()
+        .current_dir(temp_dir.path())
+        .args(["diff", "--format", "sarif", "old.env", "new.env"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "a diff cannot be written as sarif",
+        ));
+}
+
+#[test]
+fn test_diff_as_git_driver_and_textconv() {
+    let temp_dir = common::TempEnvDir::new().unwrap();
+    let dir = temp_dir.path();
+    let envcheck = assert_cmd::cargo::cargo_bin("envcheck");
+    common::init_git_repo(dir);
+    temp_dir
+        .create_env_file(".env.example", "API_KEY=abc\nLEGACY=\n")
+        .unwrap();
+    common::git(dir, &["add", "."]);
+    common::git(dir, &["commit", "-q", "-m", "init"]);
+    temp_dir
+        .create_env_file(".env.example", "API_KEY = \"abc\"\nNEW_KEY=\n")
+        .unwrap();
+
+    let driver = format!("{} diff", envcheck.display());
+    temp_dir
+        .create_env_file(".gitattributes", ".env.example diff=envcheck\n")
+        .unwrap();
+    common::git(dir, &["config", "diff.envcheck.command", &driver]);
+    let output = common::git(dir, &["diff"]);
+    assert!(
+        output.contains("--- a/.env.example\n+++ b/.env.example\n  + NEW_KEY\n  - LEGACY"),
+        "{output}"
+    );
+
+    // Renames pass two more arguments to the driver
+    common::git(dir, &["add", "."]);
+    common::git(dir, &["commit", "-q", "-m", "edit"]);
+    common::git(dir, &["mv", ".env.example", ".env.sample"]);
+    let output = common::git(dir, &["diff", "-M", "--cached"]);
+    assert!(
+        output.contains("--- a/.env.example\n+++ b/.env.sample\n  no semantic changes"),
+        "{output}"
+    );
+    common::git(dir, &["mv", ".env.sample", ".env.example"]);
+
+    // Unmerged paths come without file contents
+    envcheck_cmd()
+        .current_dir(dir)
+        .env("GIT_DIFF_PATH_TOTAL", "1")
+        .args(["diff", ".env.example"])
+        .assert()
+        .success()
+        .stdout("* Unmerged path .env.example\n");
+
+    let textconv = format!("{} diff --textconv", envcheck.display());
+    common::git(dir, &["config", "--unset", "diff.envcheck.command"]);
+    common::git(dir, &["config", "diff.envcheck.textconv", &textconv]);
+    let output = common::git(dir, &["diff", "HEAD~1"]);
+    assert!(output.contains("-LEGACY=\n+NEW_KEY="), "{output}");
+    assert!(output.contains(" API_KEY=hash:"), "{output}");
+    assert!(!output.contains("abc"), "{output}");
+}
@@ -1,8 +1,10 @@
 #![allow(deprecated)]
 use assert_cmd::Command;
+use predicates::prelude::*;
 use std::fs;
 
 mod common;
+use common::git;
 
 /// Helper to get the envcheck binary command
 fn envcheck_cmd() -> Command {
@@ -39,33 +41,269 @@ fn test_fix_preserves_comments() {
 
     envcheck_cmd().arg("fix").arg(path).assert().success();
 
+    // Each blank-line separated block is its own section, so nothing moves
     let content = fs::read_to_string(path).unwrap();
+    assert_eq!(content, format!("{input}\n"));
+}
+
+#[test]
+fn test_fix_sorts_within_sections() {
+    let input = "# App settings\nAPP_PORT=80\nAPP_HOST=x\n\n\
+                 # --- Database ---\n# primary\nDB_USER=u\nDB_HOST=h\n# trailing note\n\n\
+                 B_KEY=2\nA_KEY=1\n";
+    let temp = common::TempEnvFile::new(input).unwrap();
+    let path = temp.path();
+
+    envcheck_cmd().arg("fix").arg(path).assert().success();
+
+    let content = fs::read_to_string(path).unwrap();
+    assert_eq!(
+        content,
+        "# App settings\nAPP_HOST=x\nAPP_PORT=80\n\n\
+         # --- Database ---\n# primary\nDB_HOST=h\nDB_USER=u\n# trailing note\n\n\
+         A_KEY=1\nB_KEY=2\n"
+    );
+}
+
+#[test]
+fn test_fix_keeps_comment_on_first_entry() {
+    let temp =
+        common::TempEnvFile::new("# Database host\nDB_HOST=x\n# API key\nAPI_KEY=y\n").unwrap();
+
+    envcheck_cmd()
+        .arg("fix")
+        .arg("--stdout")
+        .arg(temp.path())
+        .assert()
+        .success()
+        .stdout("# API key\nAPI_KEY=y\n# Database host\nDB_HOST=x\n");
+}
+
+#[test]
+fn test_fix_keeps_multiline_values_together() {
+    let input = "Z_KEY=\"line one\nline two\"\nA_KEY=1\n";
+    let temp = common::TempEnvFile::new(input).unwrap();
+    let path = temp.path();
+
+    envcheck_cmd().arg("fix").arg(path).assert().success();
+
+    let content = fs::read_to_string(path).unwrap();
+    assert_eq!(content, "A_KEY=1\nZ_KEY=\"line one\nline two\"\n");
+}
+
+#[test]
+fn test_fix_keeps_exports_and_inline_comments() {
+    let input = "export B_KEY=\"two\" # second\nA_KEY = 1 # first\n";
+    let temp = common::TempEnvFile::new(input).unwrap();
+    let path = temp.path();
+
+    envcheck_cmd().arg("fix").arg(path).assert().success();
+
+    let content = fs::read_to_string(path).unwrap();
+    assert_eq!(
+        content,
+        "A_KEY = 1 # first\nexport B_KEY=\"two\" # second\n"
+    );
+}
+
+#[test]
+fn test_fix_quotes_values_for_shell_sourcing() {
+    let input = "A_KEY=foo bar\nB_KEY=$(whoami)\nC_KEY=it's ${A_KEY}\nD_KEY=\"it's `id`\"\n";
+    let temp = common::TempEnvFile::new(input).unwrap();
+    let path = temp.path();
+
+    envcheck_cmd().arg("fix").arg(path).assert().success();
+
+    let content = fs::read_to_string(path).unwrap();
+    assert_eq!(
+        content,
+        "A_KEY='foo bar'\nB_KEY='$(whoami)'\nC_KEY=\"it's ${A_KEY}\"\nD_KEY=\"it's `id`\"\n"
+    );
+}
+
+#[test]
+fn test_fix_keeps_last_duplicate() {
+    let input = "A_KEY=1\nB_KEY=${A_KEY}\nA_KEY=2\nC_KEY=3\nC_KEY=4\n";
+    let temp = common::TempEnvFile::new(input).unwrap();
+    let path = temp.path();
+
+    envcheck_cmd().arg("fix").arg(path).assert().success();
+
+    // The first A_KEY is still read by B_KEY, so it stays
+    let content = fs::read_to_string(path).unwrap();
+    assert_eq!(content, "A_KEY=1\nA_KEY=2\nB_KEY=${A_KEY}\nC_KEY=4\n");
+}
+
+#[test]
+fn test_fix_removes_export_when_configured() {
+    let temp = common::TempEnvDir::new().unwrap();
+    temp.create_env_file(".envcheckrc.yaml", "fix:\n  remove_export: true\n")
+        .unwrap();
+    let path = temp
+        .create_env_file(".env", "export A_KEY=1\nexport   B_KEY=2\n")
+        .unwrap();
+
+    envcheck_cmd()
+        .current_dir(temp.path())
+        .args(["fix", ".env"])
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(path).unwrap();
+    assert_eq!(content, "A_KEY=1\nB_KEY=2\n");
+}
+
+#[test]
+fn test_fix_check_fails_without_writing() {
+    let input = "B_KEY=2\nA_KEY=1\n";
+    let temp = common::TempEnvFile::new(input).unwrap();
+    let path = temp.path();
+
+    envcheck_cmd()
+        .args(["fix", "--check"])
+        .arg(path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "1 file(s) would be changed by fix",
+        ));
+    assert_eq!(fs::read_to_string(path).unwrap(), input);
+
+    let temp = common::TempEnvFile::new("A_KEY=1\nB_KEY=2\n").unwrap();
+    envcheck_cmd()
+        .args(["fix", "--check"])
+        .arg(temp.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_fix_diff_and_stdout() {
+    let temp = common::TempEnvDir::new().unwrap();
+    let input = "B_KEY=2 \nA_KEY=1\n";
+    let path = temp.create_env_file(".env", input).unwrap();
+
+    envcheck_cmd()
+        .current_dir(temp.path())
+        .args(["fix", "--diff", ".env"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--- a/.env\n+++ b/.env\n"))
+        .stdout(predicate::str::contains("-B_KEY=2 \n"))
+        .stdout(predicate::str::contains("+B_KEY=2\n"));
+
+    envcheck_cmd()
+        .current_dir(temp.path())
+        .args(["fix", "--stdout", ".env"])
+        .assert()
+        .success()
+        .stdout("A_KEY=1\nB_KEY=2\n");
+
+    assert_eq!(fs::read_to_string(path).unwrap(), input);
+}
+
+/// Creates a repository with one commit holding an unsorted `.env`.
+fn init_repo(config: &str) -> common::TempEnvDir {
+    let temp = common::TempEnvDir::new().unwrap();
+    let dir = temp.path();
+    common::init_git_repo(dir);
+    temp.create_env_file(".envcheckrc.yaml", config).unwrap();
+    temp.create_env_file(".env", "B_KEY=2\nA_KEY=1\n").unwrap();
+    temp.create_env_file("notes.txt", "draft\n").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "init"]);
+    temp
+}
+
+#[test]
+fn test_fix_commit_on_new_branch() {
+    let temp = init_repo("fix:\n  branch: envcheck/tidy\n  commit_message: \"chore: tidy env\"\n");
+    let dir = temp.path();
+    fs::write(dir.join("notes.txt"), "unstaged edit\n").unwrap();
+
+    envcheck_cmd()
+        .current_dir(dir)
+        .args(["fix", "--commit", ".env"])
+        .assert()
+        .success();
+
+    assert_eq!(git(dir, &["branch", "--show-current"]), "envcheck/tidy");
+    assert_eq!(git(dir, &["log", "-1", "--format=%s"]), "chore: tidy env");
+    assert_eq!(
+        git(dir, &["show", "--name-only", "--format=", "HEAD"]),
+        ".env"
+    );
+    assert_eq!(git(dir, &["show", "HEAD:.env"]), "A_KEY=1\nB_KEY=2");
+}
+
+#[test]
+fn test_fix_commit_refuses_dirty_index() {
+    let temp = init_repo("");
+    let dir = temp.path();
+    fs::write(dir.join("notes.txt"), "staged edit\n").unwrap();
+    git(dir, &["add", "notes.txt"]);
+
+    envcheck_cmd()
+        .current_dir(dir)
+        .args(["fix", "--commit", ".env"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "git index has staged changes (notes.txt)",
+        ));
+
+    assert_eq!(git(dir, &["branch", "--show-current"]), "main");
+    assert_eq!(
+        fs::read_to_string(dir.join(".env")).unwrap(),
+        "B_KEY=2\nA_KEY=1\n"
+    );
+}
+
+#[test]
+fn test_fix_commit_reports_git_failures() {
+    let temp = init_repo("fix:\n  branch: main\n");
+
+    envcheck_cmd()
+        .current_dir(temp.path())
+        .args(["fix", "--commit", ".env"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("`git checkout -b main` failed"));
+    assert_eq!(git(temp.path(), &["status", "--porcelain"]), "");
+}
+
+#[test]
+fn test_fix_pr_pushes_and_runs_pr_command() {
+    let config =
+        "fix:\n  branch: envcheck/pr\n  pr_command: [sh, -c, 'echo \"opened $0\"', '{branch}']\n";
+    let temp = init_repo(config);
+    let dir = temp.path();
+    let remote = dir.join("remote.git");
+    git(dir, &["init", "-q", "--bare", remote.to_str().unwrap()]);
+    git(dir, &["remote", "add", "origin", remote.to_str().unwrap()]);
+
+    envcheck_cmd()
+        .current_dir(dir)
+        .args(["fix", "--pr", ".env"])
+        .assert()
+        .success()
+        .stdout("opened envcheck/pr\n");
+    assert_eq!(
+        git(&remote, &["log", "-1", "--format=%s", "envcheck/pr"]),
+        "fix: auto-fix .env files via envcheck"
+    );
 
-    // Exact formatting depends on implementation (newlines between entries?)
-    // Our implementation puts one newline after each entry block
-    // So:
-    // Entry Header: "# Header\n"
-    // Entry A: "# Key A\nA_KEY=1\n"
-    // Entry B: "# Key B\nB_KEY=2\n"
-    // Footer: "# Footer\n"
-
-    // Wait, parser groups comments.
-    // If blank lines existed, parser kept them?
-    // My parser implementation:
-    // `current_comments.push(trimmed)` -> trimmed blank line is empty string?
-    // No, `if trimmed.is_empty() { continue }`
-    // So blank lines are LOST.
-
-    // Thus comments will be packed tight.
-    // Output:
-    // # Header
-    // # Key A
-    // A_KEY=1
-    // # Key B
-    // B_KEY=2
-    // # Footer
-
-    // Let's verify THAT structure.
-    let expected_tight = "# Header\n# Key A\nA_KEY=1\n# Key B\nB_KEY=2\n# Footer\n";
-    assert_eq!(content, expected_tight);
+    fs::write(dir.join(".env"), "D_KEY=4\nC_KEY=3\n").unwrap();
+    git(dir, &["commit", "-q", "-am", "unsorted again"]);
+    fs::write(
+        dir.join(".envcheckrc.yaml"),
+        "fix:\n  branch: envcheck/pr2\n  pr_command: [sh, -c, 'exit 3']\n",
+    )
+    .unwrap();
+    envcheck_cmd()
+        .current_dir(dir)
+        .args(["fix", "--pr", ".env"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("PR command `sh -c exit 3` failed"));
 }
@@ -147,3 +147,32 @@ fn test_k8s_sync_requires_env_flag() {
         .failure()
         .stderr(predicate::str::contains("--env"));
 }
+
+#[test]
+fn test_k8s_sync_optional_keys() {
+    let manifest_content = r#"
+apiVersion: v1
+kind: ConfigMap
+metadata:
+  name: test-config
+data:
+  SENTRY_DSN: "value"
+  REQUIRED_KEY: "value"
+"#;
+    let temp_manifest = common::TempK8sManifest::new(manifest_content).unwrap();
+    let temp_env = common::TempEnvFile::new("# SENTRY_DSN=\n").unwrap();
+
+    envcheck_cmd()
+        .args(["k8s-sync", "--compact"])
+        .arg(temp_manifest.path())
+        .arg("--env")
+        .arg(temp_env.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "warning[W005]: Key 'REQUIRED_KEY' found in K8s ConfigMap/test-config",
+        ))
+        .stdout(predicate::str::contains(
+            "info[W005]: Optional key 'SENTRY_DSN' found in K8s ConfigMap/test-config",
+        ));
+}